@@ -0,0 +1,89 @@
+//! Deterministic time and message traces for testing actors written with
+//! [actix-handler-macro](https://docs.rs/actix-handler-macro).
+//!
+//! actix timers (`run_interval`, `run_later`, `clock::sleep`) run on tokio's clock. Pausing it
+//! inside the `System` makes time virtual: it only moves while a test waits in [`advance`], and
+//! then instantly, so interval and timeout behaviour can be asserted without waiting on the wall
+//! clock. [`Trace`] is an `#[actix_handler(observer = ...)]` recording handled messages in
+//! delivery order.
+//!
+//! ```ignore
+//! #[actix_handler(observer = "actix_handler_sim::Trace")]
+//! impl Janitor {
+//!     #[interval(secs = 60)]
+//!     fn tick(&mut self, ctx: &mut Context<Self>) {
+//!         ctx.notify(Sweep);
+//!     }
+//!     // ...
+//! }
+//!
+//! System::new().block_on(async {
+//!     actix_handler_sim::pause();
+//!     let addr = Janitor::default().start();
+//!
+//!     actix_handler_sim::advance(Duration::from_secs(190)).await; // returns instantly
+//!     assert_eq!(addr.count_sweeps(CountSweeps).await.unwrap(), 3);
+//! });
+//! assert_eq!(actix_handler_sim::Trace::take()[..3], ["Janitor::sweep"; 3]);
+//! ```
+//!
+//! Like actix-handler-macro, the `actix-0-10` (default), `actix-0-12` and `actix-0-13` features
+//! pick the actix release, which decides the tokio release whose clock is paused. When several
+//! are enabled the newest one wins; without any, only [`Trace`] is available.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+#[cfg(all(
+    feature = "actix-0-10",
+    not(any(feature = "actix-0-12", feature = "actix-0-13"))
+))]
+use tokio_0_2 as tokio;
+#[cfg(any(feature = "actix-0-12", feature = "actix-0-13"))]
+use tokio_1 as tokio;
+
+thread_local! {
+    static TRACE: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Freezes the clock of the current `System`. Must be called from inside `block_on`.
+#[cfg(any(feature = "actix-0-10", feature = "actix-0-12", feature = "actix-0-13"))]
+pub fn pause() {
+    tokio::time::pause();
+}
+
+/// Moves virtual time forward, firing every timer due in the meantime. A paused clock jumps
+/// straight to the next timer once every task is idle, so sleeping lets timers fire in deadline
+/// order, each followed by the work it wakes up.
+#[cfg(any(feature = "actix-0-10", feature = "actix-0-12", feature = "actix-0-13"))]
+pub async fn advance(duration: Duration) {
+    #[cfg(all(
+        feature = "actix-0-10",
+        not(any(feature = "actix-0-12", feature = "actix-0-13"))
+    ))]
+    tokio::time::delay_for(duration).await;
+    #[cfg(any(feature = "actix-0-12", feature = "actix-0-13"))]
+    tokio::time::sleep(duration).await;
+}
+
+/// Records every message handled on the current thread as `"Actor::method"`
+pub struct Trace;
+
+impl Trace {
+    pub fn before_handle(actor: &'static str, method: &'static str, _message: &'static str) {
+        TRACE.with(|trace| trace.borrow_mut().push(format!("{}::{}", actor, method)));
+    }
+
+    pub fn after_handle(
+        _actor: &'static str,
+        _method: &'static str,
+        _message: &'static str,
+        _elapsed: Duration,
+    ) {
+    }
+
+    /// Takes the messages handled so far, oldest first
+    pub fn take() -> Vec<String> {
+        TRACE.with(|trace| trace.borrow_mut().drain(..).collect())
+    }
+}
@@ -1,7 +1,15 @@
-use actix::{Actor, Addr, Message, MessageResponse, System, SystemRunner};
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Addr, Message, MessageResponse, SystemRunner};
 use actix_handler_macro::{actix_handler, Actor};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
+#[path = "../tests/common/mod.rs"]
+mod common;
+
 #[derive(Actor)]
 struct Example;
 
@@ -43,8 +51,8 @@ fn bench_send_msg_trait(sys: &mut SystemRunner, addr: Addr<Example>, greeting: G
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    let mut sys = System::new("actix-test-runtime");
-    let addr = Example {}.start();
+    let mut sys = common::system();
+    let addr = sys.block_on(async { Example {}.start() });
 
     c.bench_function("bench send msg trait", |b| {
         b.iter(|| bench_send_msg_trait(&mut sys, addr.clone(), black_box(Greeting {})))
@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Ident, Path};
+
+/// The actix release generated code targets, chosen with the `actix-0-*` cargo features. When
+/// several are enabled the newest one wins, so features stay additive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActixVersion {
+    V0_10,
+    V0_12,
+    V0_13,
+}
+
+pub fn actix_version() -> ActixVersion {
+    if cfg!(feature = "actix-0-13") {
+        ActixVersion::V0_13
+    } else if cfg!(feature = "actix-0-12") {
+        ActixVersion::V0_12
+    } else {
+        ActixVersion::V0_10
+    }
+}
+
+/// Implements `MessageResponse` for a type replying with itself, like actix's own
+/// `#[derive(MessageResponse)]`. actix 0.12 replaced the `ResponseChannel` type parameter with
+/// a concrete `OneshotSender`.
+pub fn expand_message_response_impl(actix: &Path, name: &Ident) -> TokenStream2 {
+    let handle = match actix_version() {
+        ActixVersion::V0_10 => quote!(
+            fn handle<R: #actix::dev::ResponseChannel<M>>(
+                self,
+                _: &mut A::Context,
+                tx: Option<R>,
+            ) {
+                if let Some(tx) = tx {
+                    tx.send(self);
+                }
+            }
+        ),
+        ActixVersion::V0_12 | ActixVersion::V0_13 => quote!(
+            fn handle(self, _: &mut A::Context, tx: Option<#actix::dev::OneshotSender<Self>>) {
+                if let Some(tx) = tx {
+                    let _ = tx.send(self);
+                }
+            }
+        ),
+    };
+
+    quote!(
+        impl<A, M> #actix::dev::MessageResponse<A, M> for #name
+        where
+            A: #actix::Actor,
+            M: #actix::Message<Result = #name>,
+        {
+            #handle
+        }
+    )
+}
+
+/// A future resolving after `duration`. actix 0.12 re-exports tokio 1, where `delay_for` was
+/// renamed to `sleep`.
+pub fn expand_sleep(actix: &Path, duration: TokenStream2) -> TokenStream2 {
+    match actix_version() {
+        ActixVersion::V0_10 => quote!(#actix::clock::delay_for(#duration)),
+        ActixVersion::V0_12 | ActixVersion::V0_13 => quote!(#actix::clock::sleep(#duration)),
+    }
+}
+
+/// A fresh `SystemRunner`. actix 0.12 dropped the system name argument.
+pub fn expand_system_new(actix: &Path) -> TokenStream2 {
+    match actix_version() {
+        ActixVersion::V0_10 => quote!(#actix::System::new("actix-test-runtime")),
+        ActixVersion::V0_12 | ActixVersion::V0_13 => quote!(#actix::System::new()),
+    }
+}
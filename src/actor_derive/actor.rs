@@ -1,33 +1,167 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::Meta;
+use syn::{Lit, Meta, Path};
 
-use crate::actor_derive::utils::find_attribute_meta;
+use crate::actix_version::{actix_version, ActixVersion};
+use crate::actor_derive::publishes;
+use crate::actor_derive::supervised::{self, SUPERVISE_STOPPING_METHOD};
+use crate::actor_derive::utils::{
+    find_actix_path, find_actor_arg, find_attribute_meta, has_actor_flag,
+};
+use crate::expand_timers::START_TIMERS_METHOD;
 
 pub const ACTOR_ATTR: &str = "actor";
 
 pub fn expand(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
+    let actix = match find_actix_path(ast) {
+        Ok(actix) => actix,
+        Err(err) => return err.to_compile_error(),
+    };
 
-    let context_type = find_attribute_meta(ast, ACTOR_ATTR)
-        .and_then(get_context_type)
-        .map(|t| t.into_token_stream())
-        .unwrap_or(quote! { ::actix::dev::Context });
+    let context = find_attribute_meta(ast, ACTOR_ATTR).and_then(get_context_type);
+    let is_sync_context = context.as_ref().is_some_and(is_sync_context);
+    let context_type = context
+        .map(|t| qualify_context_type(&actix, t))
+        .unwrap_or(quote! { #actix::dev::Context });
+
+    let started = if has_actor_flag(ast, "timers") {
+        let start_timers = syn::Ident::new(START_TIMERS_METHOD, proc_macro2::Span::call_site());
+        quote! {
+            fn started(&mut self, ctx: &mut Self::Context) {
+                self.#start_timers(ctx);
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let stopping = if supervised::has_restart_policy(ast) {
+        let supervise_stopping =
+            syn::Ident::new(SUPERVISE_STOPPING_METHOD, proc_macro2::Span::call_site());
+        quote! {
+            fn stopping(&mut self, ctx: &mut Self::Context) -> #actix::Running {
+                self.#supervise_stopping(ctx)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let start_sync_pool = expand_start_sync_pool(ast, &actix, is_sync_context);
+    let start_on_new_arbiter = expand_start_on_new_arbiter(ast, &actix);
+    let publishes = publishes::expand(ast, &actix);
 
     quote! {
-        impl ::actix::Actor for #name {
+        impl #actix::Actor for #name {
             type Context = #context_type<Self>;
+
+            #started
+            #stopping
         }
+
+        #start_sync_pool
+        #start_on_new_arbiter
+        #publishes
     }
 }
 
-fn get_context_type(meta: Meta) -> Option<syn::Type> {
-    if let syn::Meta::List(ref list) = meta {
-        if list.nested.is_empty() {
-            return None;
+/// `#[actor(threads = 4)]` starts a `SyncArbiter` of default-constructed actors, which only runs
+/// actors with a `SyncContext`
+fn expand_start_sync_pool(
+    ast: &syn::DeriveInput,
+    actix: &Path,
+    is_sync_context: bool,
+) -> TokenStream {
+    let threads = match find_actor_arg(ast, "threads") {
+        Some(threads) => threads,
+        None => return quote! {},
+    };
+    if !is_sync_context {
+        return syn::Error::new_spanned(
+            threads,
+            "#[actor(threads = ...)] starts a `SyncArbiter`, which needs #[actor(context = \"SyncContext\")]",
+        )
+        .to_compile_error();
+    }
+    let threads: usize = match &threads {
+        Lit::Int(threads) => match threads.base10_parse() {
+            Ok(threads) => threads,
+            Err(err) => return err.to_compile_error(),
+        },
+        _ => {
+            return syn::Error::new_spanned(threads, "expected a number of threads")
+                .to_compile_error()
+        }
+    };
+    let name = &ast.ident;
+
+    quote! {
+        impl #name {
+            /// Starts this actor on a `SyncArbiter` with its configured number of threads
+            #[allow(dead_code)]
+            pub fn start_sync_pool() -> #actix::Addr<Self> {
+                #actix::SyncArbiter::start(#threads, <Self as ::std::default::Default>::default)
+            }
+        }
+    }
+}
+
+/// `#[actor(arbiter = "new")]` starts the actor on its own arbiter
+fn expand_start_on_new_arbiter(ast: &syn::DeriveInput, actix: &Path) -> TokenStream {
+    match find_actor_arg(ast, "arbiter") {
+        Some(Lit::Str(arbiter)) if arbiter.value() == "new" => {}
+        _ => return quote! {},
+    }
+    let name = &ast.ident;
+    let arbiter = match actix_version() {
+        ActixVersion::V0_10 => quote!(&#actix::Arbiter::new()),
+        ActixVersion::V0_12 | ActixVersion::V0_13 => quote!(&#actix::Arbiter::new().handle()),
+    };
+
+    quote! {
+        impl #name {
+            /// Starts this actor on a newly spawned arbiter
+            #[allow(dead_code)]
+            pub fn start_on_new_arbiter(self) -> #actix::Addr<Self> {
+                <Self as #actix::Actor>::start_in_arbiter(#arbiter, move |_| self)
+            }
+        }
+    }
+}
+
+/// `SyncContext` and `Context` may be written without a path, as in
+/// `#[actor(context = "SyncContext")]`
+fn qualify_context_type(actix: &Path, ty: syn::Type) -> TokenStream {
+    match &ty {
+        syn::Type::Path(path) if path.qself.is_none() => {
+            if path.path.is_ident("SyncContext") {
+                return quote! { #actix::SyncContext };
+            }
+            if path.path.is_ident("Context") {
+                return quote! { #actix::Context };
+            }
+            ty.into_token_stream()
         }
+        _ => ty.into_token_stream(),
+    }
+}
 
-        get_context_type_from_meta(&list.nested[0])
+/// Whether `#[actor(context = "...")]` names actix's `SyncContext`, however it's qualified
+fn is_sync_context(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "SyncContext"),
+        _ => false,
+    }
+}
+
+fn get_context_type(meta: Meta) -> Option<syn::Type> {
+    if let syn::Meta::List(ref list) = meta {
+        list.nested.iter().find_map(get_context_type_from_meta)
     } else {
         None
     }
@@ -36,6 +170,14 @@ fn get_context_type(meta: Meta) -> Option<syn::Type> {
 fn get_context_type_from_meta(item: &syn::NestedMeta) -> Option<syn::Type> {
     match item {
         syn::NestedMeta::Lit(syn::Lit::Str(ref s)) => syn::parse_str::<syn::Type>(&s.value()).ok(),
+        syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))
+            if name_value.path.is_ident("context") =>
+        {
+            match &name_value.lit {
+                syn::Lit::Str(s) => syn::parse_str::<syn::Type>(&s.value()).ok(),
+                _ => None,
+            }
+        }
         _ => None,
     }
 }
@@ -1,4 +1,6 @@
 pub mod actor;
 pub mod arbiter_service;
+pub mod publishes;
 pub mod supervised;
+pub mod system_service;
 mod utils;
@@ -0,0 +1,175 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, Fields, Meta, NestedMeta, Path};
+
+use crate::utils::{compilation_error, to_snake_case};
+
+pub const PUBLISHES_ATTR: &str = "publishes";
+
+/// Expands the subscriber list, `...Subscribe<Event>`/`...Unsubscribe<Event>` handlers and
+/// `publish_*` helpers for the events listed in `#[publishes(...)]`
+pub fn expand(ast: &syn::DeriveInput, actix: &Path) -> TokenStream {
+    let events = find_events(ast);
+    if events.is_empty() {
+        return quote! {};
+    }
+
+    let subscribers_field = match find_subscribers_field(ast) {
+        Some(field) => field,
+        None => return compilation_error(
+            "#[publishes(...)] needs a field marked #[publishes(subscribers)] to hold subscribers",
+        )
+        .into(),
+    };
+
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let subscribers_name = format_ident!("{}Subscribers", name);
+    let subscribe_name = format_ident!("{}Subscribe", name);
+    let unsubscribe_name = format_ident!("{}Unsubscribe", name);
+    let topics: Vec<_> = events
+        .iter()
+        .map(|event| {
+            let ident = &event.segments.last().unwrap().ident;
+            format_ident!("{}", to_snake_case(&ident.to_string()))
+        })
+        .collect();
+
+    let subscriber_fields = events
+        .iter()
+        .zip(&topics)
+        .map(|(event, topic)| quote!(#topic: ::std::vec::Vec<#actix::Recipient<#event>>));
+
+    let handlers = events.iter().zip(&topics).map(|(event, topic)| {
+        let publish = format_ident!("publish_{}", topic);
+        let doc = format!(
+            "Sends `{}` to every subscriber, dropping those that have stopped",
+            quote!(#event).to_string().replace(' ', "")
+        );
+        quote!(
+            impl #actix::Handler<#subscribe_name<#event>> for #name {
+                type Result = ();
+
+                fn handle(
+                    &mut self,
+                    msg: #subscribe_name<#event>,
+                    _ctx: &mut <Self as #actix::Actor>::Context,
+                ) {
+                    let subscribers = &mut self.#subscribers_field.#topic;
+                    if !subscribers.contains(&msg.0) {
+                        subscribers.push(msg.0);
+                    }
+                }
+            }
+
+            impl #actix::Handler<#unsubscribe_name<#event>> for #name {
+                type Result = ();
+
+                fn handle(
+                    &mut self,
+                    msg: #unsubscribe_name<#event>,
+                    _ctx: &mut <Self as #actix::Actor>::Context,
+                ) {
+                    self.#subscribers_field.#topic.retain(|subscriber| subscriber != &msg.0);
+                }
+            }
+
+            impl #name {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                #vis fn #publish(
+                    &mut self,
+                    _ctx: &mut <Self as #actix::Actor>::Context,
+                    event: #event,
+                ) {
+                    self.#subscribers_field.#topic.retain(|subscriber| {
+                        !matches!(
+                            subscriber.try_send(::std::clone::Clone::clone(&event)),
+                            Err(#actix::prelude::SendError::Closed(_))
+                        )
+                    });
+                }
+            }
+        )
+    });
+
+    quote! {
+        /// Subscribers to the events an actor publishes
+        #[derive(Default)]
+        #vis struct #subscribers_name {
+            #(#subscriber_fields,)*
+        }
+
+        /// Subscribes a recipient to events of type `M`
+        #vis struct #subscribe_name<M>(pub #actix::Recipient<M>)
+        where
+            M: #actix::Message + Send,
+            M::Result: Send;
+
+        impl<M> #actix::Message for #subscribe_name<M>
+        where
+            M: #actix::Message + Send,
+            M::Result: Send,
+        {
+            type Result = ();
+        }
+
+        /// Unsubscribes a recipient from events of type `M`
+        #vis struct #unsubscribe_name<M>(pub #actix::Recipient<M>)
+        where
+            M: #actix::Message + Send,
+            M::Result: Send;
+
+        impl<M> #actix::Message for #unsubscribe_name<M>
+        where
+            M: #actix::Message + Send,
+            M::Result: Send,
+        {
+            type Result = ();
+        }
+
+        #(#handlers)*
+    }
+}
+
+fn find_events(ast: &syn::DeriveInput) -> Vec<Path> {
+    ast.attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(PUBLISHES_ATTR))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            NestedMeta::Meta(Meta::Path(path)) => Some(path),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The field marked `#[publishes(subscribers)]`
+fn find_subscribers_field(ast: &syn::DeriveInput) -> Option<syn::Ident> {
+    let fields = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    fields
+        .iter()
+        .find(|field| {
+            field.attrs.iter().any(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) if list.path.is_ident(PUBLISHES_ATTR) => {
+                    list.nested.iter().any(|item| match item {
+                        NestedMeta::Meta(Meta::Path(path)) => path.is_ident("subscribers"),
+                        _ => false,
+                    })
+                }
+                _ => false,
+            })
+        })
+        .and_then(|field| field.ident.clone())
+}
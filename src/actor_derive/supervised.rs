@@ -1,10 +1,289 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::{Data, Fields, Meta, NestedMeta, Path};
+
+use crate::actix_version::expand_sleep;
+use crate::actor_derive::utils::{find_actix_path, find_attribute_meta};
+use crate::options::{lit_int, lit_str, parse_path, unknown_option};
+use crate::utils::compilation_error;
+
+pub const SUPERVISED_ATTR: &str = "supervised";
+/// Inherent method the generated `stopping` of `#[derive(Actor)] #[supervised(...)]` calls
+pub const SUPERVISE_STOPPING_METHOD: &str = "supervise_stopping";
+const RECORD_RESTART_METHOD: &str = "record_restart";
+
+/// `#[supervised(...)]` restart policy
+#[derive(Default)]
+struct RestartPolicy {
+    max_restarts: Option<usize>,
+    within_secs: Option<u64>,
+    backoff: Backoff,
+    backoff_millis: u64,
+    on_exhausted: Option<Path>,
+}
+
+#[derive(Default)]
+enum Backoff {
+    /// Restart straight away
+    #[default]
+    None,
+    /// Wait `backoff_millis` before handling messages again
+    Constant,
+    /// Wait `backoff_millis`, doubled for every restart within the window
+    Exponential,
+}
 
 pub fn expand(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
+    let actix = match find_actix_path(ast) {
+        Ok(actix) => actix,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let policy = match find_attribute_meta(ast, SUPERVISED_ATTR) {
+        Some(Meta::List(list)) => match parse_restart_policy(list.nested.iter()) {
+            Ok(policy) => policy,
+            Err(err) => {
+                let err = err.to_compile_error();
+                let supervise_stopping =
+                    expand_supervise_stopping(&actix, quote!(#actix::Running::Stop));
+                // `#[derive(Actor)]` still calls the method, so only the policy error is reported
+                return quote! {
+                    #err
+                    impl #name { #supervise_stopping }
+                    impl #actix::Supervised for #name {}
+                };
+            }
+        },
+        _ => {
+            return quote! {
+                impl #actix::Supervised for #name {}
+            }
+        }
+    };
+
+    let state_field =
+        match find_state_field(ast) {
+            Some(field) => field,
+            None => return compilation_error(
+                "#[supervised(...)] needs a field marked #[supervised(state)] to track restarts",
+            )
+            .into(),
+        };
+
+    let vis = &ast.vis;
+    let state_name = format_ident!("{}RestartState", name);
+    let max_restarts = match policy.max_restarts {
+        Some(max_restarts) => quote!(#max_restarts),
+        None => quote!(usize::MAX),
+    };
+    let expire_restarts = policy.within_secs.map(|within_secs| {
+        quote!(
+            let within = ::std::time::Duration::from_secs(#within_secs);
+            while let Some(restart) = state.restarts.front() {
+                if now.duration_since(*restart) > within {
+                    state.restarts.pop_front();
+                } else {
+                    break;
+                }
+            }
+        )
+    });
+    let backoff_millis = policy.backoff_millis;
+    let delay = match policy.backoff {
+        Backoff::None => None,
+        Backoff::Constant => Some(quote!(::std::time::Duration::from_millis(#backoff_millis))),
+        Backoff::Exponential => Some(quote!(::std::time::Duration::from_millis(
+            #backoff_millis.saturating_mul(1u64 << ::std::cmp::min(restarts - 1, 32) as u32)
+        ))),
+    };
+    let delay_restart = delay.map(|delay| {
+        let sleep = expand_sleep(&actix, delay);
+        quote!(
+            #actix::AsyncContext::wait(ctx, #actix::fut::wrap_future::<_, Self>(#sleep));
+        )
+    });
+    let on_exhausted = policy
+        .on_exhausted
+        .map(|on_exhausted| quote!(#on_exhausted(self, ctx);));
+    let record_restart = format_ident!("{}", RECORD_RESTART_METHOD);
+    let supervise_stopping = expand_supervise_stopping(
+        &actix,
+        quote! {
+            if #actix::ActorContext::state(ctx) != #actix::ActorState::Stopping
+                || self.#state_field.exhausted
+                || !self.#record_restart(ctx)
+            {
+                return #actix::Running::Stop;
+            }
+            #actix::Running::Continue
+        },
+    );
+
+    quote! {
+        /// Restarts of a supervised actor, tracked by its `#[supervised(...)]` policy
+        #[derive(Default)]
+        #vis struct #state_name {
+            restarts: ::std::collections::VecDeque<::std::time::Instant>,
+            exhausted: bool,
+        }
+
+        impl #state_name {
+            /// Restarts counted against the current window
+            #[allow(dead_code)]
+            #vis fn restarts(&self) -> usize {
+                self.restarts.len()
+            }
+
+            /// Whether the restart budget has run out
+            #[allow(dead_code)]
+            #vis fn is_exhausted(&self) -> bool {
+                self.exhausted
+            }
+        }
+
+        impl #name {
+            /// Counts a restart against the policy and delays the actor by its backoff. Returns
+            /// `false`, after calling `on_exhausted`, once the budget has run out.
+            fn #record_restart(&mut self, ctx: &mut #actix::Context<Self>) -> bool {
+                let now = ::std::time::Instant::now();
+                let state = &mut self.#state_field;
+                state.restarts.push_back(now);
+                #expire_restarts
+                let restarts = state.restarts.len();
+
+                if restarts > #max_restarts {
+                    state.exhausted = true;
+                    #on_exhausted
+                    return false;
+                }
+
+                #delay_restart
+                true
+            }
 
+            #supervise_stopping
+        }
+
+        impl #actix::Supervised for #name {
+            fn restarting(&mut self, ctx: &mut Self::Context) {
+                // actix's `Supervisor` restarts the actor for as long as an address to it is
+                // alive, so an exhausted actor is parked rather than handling messages again
+                if self.#state_field.exhausted || !self.#record_restart(ctx) {
+                    #actix::AsyncContext::wait(
+                        ctx,
+                        #actix::fut::wrap_future::<_, Self>(::std::future::pending::<()>()),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The inherent method `#[derive(Actor)]` calls from `stopping`, restarting an actor that stopped
+/// itself in place while its policy allows
+fn expand_supervise_stopping(actix: &Path, body: TokenStream) -> TokenStream {
+    let supervise_stopping = format_ident!("{}", SUPERVISE_STOPPING_METHOD);
     quote! {
-        impl ::actix::Supervised for #name {}
+        #[allow(unused_variables)]
+        fn #supervise_stopping(&mut self, ctx: &mut #actix::Context<Self>) -> #actix::Running {
+            #body
+        }
+    }
+}
+
+/// Whether `#[supervised(...)]` sets a restart policy, which `#[derive(Actor)]` applies from
+/// `stopping`
+pub fn has_restart_policy(ast: &syn::DeriveInput) -> bool {
+    matches!(
+        find_attribute_meta(ast, SUPERVISED_ATTR),
+        Some(Meta::List(_))
+    )
+}
+
+fn parse_restart_policy<'a>(
+    nested: impl Iterator<Item = &'a NestedMeta>,
+) -> syn::Result<RestartPolicy> {
+    let mut policy = RestartPolicy {
+        backoff_millis: 100,
+        ..RestartPolicy::default()
+    };
+
+    for item in nested {
+        let name_value = match item {
+            NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+            NestedMeta::Meta(meta) => return Err(unknown_option(SUPERVISED_ATTR, meta.path())),
+            NestedMeta::Lit(lit) => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "expected a #[supervised] option",
+                ))
+            }
+        };
+        let lit = &name_value.lit;
+        let key = name_value.path.get_ident().map(|ident| ident.to_string());
+        match key.as_deref() {
+            Some("max_restarts") => policy.max_restarts = Some(lit_int(lit)?.base10_parse()?),
+            Some("within_secs") => policy.within_secs = Some(lit_int(lit)?.base10_parse()?),
+            Some("backoff_millis") => policy.backoff_millis = lit_int(lit)?.base10_parse()?,
+            Some("backoff") => {
+                let backoff = lit_str(lit)?;
+                policy.backoff = match backoff.value().as_str() {
+                    "constant" => Backoff::Constant,
+                    "exponential" => Backoff::Exponential,
+                    value => {
+                        return Err(syn::Error::new_spanned(
+                            backoff,
+                            format!(
+                                "expected \"constant\" or \"exponential\", found \"{}\"",
+                                value
+                            ),
+                        ))
+                    }
+                };
+            }
+            Some("on_exhausted") => policy.on_exhausted = Some(parse_path(lit_str(lit)?)?),
+            _ => return Err(unknown_option(SUPERVISED_ATTR, &name_value.path)),
+        }
+    }
+
+    Ok(policy)
+}
+
+/// The field marked `#[supervised(state)]`, holding the generated restart state
+fn find_state_field(ast: &syn::DeriveInput) -> Option<TokenStream> {
+    let fields = match &ast.data {
+        Data::Struct(data) => &data.fields,
+        _ => return None,
+    };
+
+    let is_state = |attrs: &[syn::Attribute]| {
+        attrs.iter().any(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) if list.path.is_ident(SUPERVISED_ATTR) => {
+                list.nested.iter().any(|item| match item {
+                    NestedMeta::Meta(Meta::Path(path)) => path.is_ident("state"),
+                    _ => false,
+                })
+            }
+            _ => false,
+        })
+    };
+
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .find(|field| is_state(&field.attrs))
+            .and_then(|field| field.ident.as_ref())
+            .map(|ident| quote!(#ident)),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .position(|field| is_state(&field.attrs))
+            .map(|index| {
+                let index = syn::Index::from(index);
+                quote!(#index)
+            }),
+        Fields::Unit => None,
     }
 }
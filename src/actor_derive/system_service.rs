@@ -0,0 +1,19 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::actor_derive::utils::{expand_registry_accessor, find_actix_path};
+
+pub fn expand(ast: &syn::DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let actix = match find_actix_path(ast) {
+        Ok(actix) => actix,
+        Err(err) => return err.to_compile_error(),
+    };
+    let accessor = expand_registry_accessor(ast, &actix, &quote!(#actix::SystemService));
+
+    quote! {
+        impl #actix::SystemService for #name {}
+
+        #accessor
+    }
+}
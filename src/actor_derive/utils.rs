@@ -1,4 +1,10 @@
-use syn::Meta;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Lit, Meta, NestedMeta, Path};
+
+use crate::actor_derive::actor::ACTOR_ATTR;
+use crate::options::{lit_str, parse_path};
+use crate::utils::to_snake_case;
 
 /// Finds an attribute matching an identifier
 pub fn find_attribute_meta(ast: &syn::DeriveInput, attribute: &str) -> Option<Meta> {
@@ -13,3 +19,58 @@ pub fn find_attribute_meta(ast: &syn::DeriveInput, attribute: &str) -> Option<Me
         })
     })
 }
+
+/// Whether `#[actor(...)]` contains a bare flag such as `#[actor(timers)]`
+pub fn has_actor_flag(ast: &syn::DeriveInput, flag: &str) -> bool {
+    match find_attribute_meta(ast, ACTOR_ATTR) {
+        Some(Meta::List(list)) => list.nested.iter().any(|item| match item {
+            NestedMeta::Meta(Meta::Path(path)) => path.is_ident(flag),
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+/// The literal set for `key` in `#[actor(key = ...)]`
+pub fn find_actor_arg(ast: &syn::DeriveInput, key: &str) -> Option<Lit> {
+    let nested = match find_attribute_meta(ast, ACTOR_ATTR) {
+        Some(Meta::List(list)) => list.nested,
+        _ => return None,
+    };
+
+    nested.into_iter().find_map(|item| match item {
+        NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident(key) => {
+            Some(name_value.lit)
+        }
+        _ => None,
+    })
+}
+
+/// The path generated code uses to refer to actix, set with `#[actor(crate = "...")]`
+pub fn find_actix_path(ast: &syn::DeriveInput) -> syn::Result<Path> {
+    match find_actor_arg(ast, "crate") {
+        Some(actix_path) => parse_path(lit_str(&actix_path)?),
+        None => Ok(parse_quote!(::actix)),
+    }
+}
+
+/// A free function fetching the actor from the registry of `service`, e.g. `adder_addr()` for
+/// `Adder`, so consumers only need the generated `...Addr` trait in scope
+pub fn expand_registry_accessor(
+    ast: &syn::DeriveInput,
+    actix: &Path,
+    service: &TokenStream,
+) -> TokenStream {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let accessor = format_ident!("{}_addr", to_snake_case(&name.to_string()));
+    let doc = format!("The `{}` running in the registry", name);
+
+    quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        #vis fn #accessor() -> #actix::Addr<#name> {
+            <#name as #service>::from_registry()
+        }
+    }
+}
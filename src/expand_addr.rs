@@ -1,11 +1,15 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::punctuated::Punctuated;
-use syn::{Path, PathSegment, Token, Type, TypePath};
+use syn::spanned::Spanned;
+use syn::{Ident, Path, PathSegment, Token, Type, TypePath};
 
 use crate::expand_method_handlers::HandlerContext;
+use crate::expand_protocol::implemented_trait;
+use crate::expand_trace_context::{expand_sent_message, expand_sent_type};
 use crate::options::Options;
+use crate::utils::same_path;
 
 pub struct ImplContext {
     pub(crate) type_name: Type,
@@ -13,6 +17,10 @@ pub struct ImplContext {
 }
 
 pub fn expand_addr_trait(options: Options, impl_context: ImplContext) -> TokenStream {
+    if !options.implements.is_empty() {
+        return expand_implemented_traits(&options, &impl_context);
+    }
+
     let trait_type_name: Type =
         build_renamed_addr_type(&options.trait_name, &impl_context.type_name);
     let trait_block = expand_trait_declaration(
@@ -35,18 +43,46 @@ pub fn expand_addr_trait(options: Options, impl_context: ImplContext) -> TokenSt
     ))
 }
 
+/// With `no_trait_decl, implements = "..."`, implements each trait declared elsewhere with the
+/// handlers assigned to it
+fn expand_implemented_traits(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+
+    let impls = options.implements.iter().map(|trait_path| {
+        let methods = impl_context
+            .handlers
+            .iter()
+            .filter(|handler_context| !handler_context.options.no_addr)
+            .filter(|handler_context| {
+                implemented_trait(options, handler_context)
+                    .is_some_and(|implemented| same_path(implemented, trait_path))
+            })
+            .map(|handler_context| expand_addr_method(options, type_name.clone(), handler_context));
+
+        quote_spanned!(trait_path.span() =>
+            impl #trait_path for #actix::Addr<#type_name> {
+                #(#methods)*
+            }
+        )
+    });
+
+    TokenStream::from(quote!(#(#impls)*))
+}
+
 fn expand_impl_declaration(
     options: &Options,
     impl_context: &ImplContext,
     type_name: &Type,
     trait_type_name: Type,
 ) -> TokenStream2 {
-    let trait_impls = expand_trait_impls(&options, impl_context, &type_name);
+    let actix = &options.actix_path;
+    let trait_impls = expand_trait_impls(options, impl_context, type_name);
     if options.no_trait_impl {
         quote!()
     } else {
         quote!(
-            impl #trait_type_name for ::actix::Addr<#type_name> {
+            impl #trait_type_name for #actix::Addr<#type_name> {
                 #trait_impls
             }
         )
@@ -59,7 +95,7 @@ fn expand_trait_declaration(
     type_name: &Type,
     trait_type_name: &Type,
 ) -> TokenStream2 {
-    let trait_fns = expand_trait_methods(&options, &impl_context, &type_name);
+    let trait_fns = expand_trait_methods(options, impl_context, type_name);
     if options.no_trait_decl {
         quote!()
     } else {
@@ -79,7 +115,8 @@ fn expand_trait_impls(
     impl_context
         .handlers
         .iter()
-        .map(|handler_context| expand_addr_method(&options, type_name.clone(), handler_context))
+        .filter(|handler_context| !handler_context.options.no_addr)
+        .map(|handler_context| expand_addr_method(options, type_name.clone(), handler_context))
         .fold(TokenStream2::new(), |mut m, i| {
             m.extend(i);
             m
@@ -91,29 +128,34 @@ fn expand_trait_methods(
     impl_context: &ImplContext,
     type_name: &Type,
 ) -> TokenStream2 {
+    let actix = &options.actix_path;
     impl_context
         .handlers
         .iter()
+        .filter(|handler_context| !handler_context.options.no_addr)
         .map(|handler_context| {
-            let HandlerContext {
-                method_name,
-                message_type_name,
-                ..
-            } = handler_context;
+            let message_type_name = &handler_context.message_type_name;
+            let sent_type = expand_sent_type(options, type_name, message_type_name);
+            let method_name = addr_method_name(options, handler_context);
+            let many_method = expand_many_method(options, handler_context);
 
             if options.use_recipient {
                 quote!(
                     fn #method_name(
                         self: &Self,
                         msg: #message_type_name
-                    ) -> ::actix::prelude::RecipientRequest<#message_type_name>;
+                    ) -> #actix::prelude::RecipientRequest<#sent_type>;
+
+                    #many_method
                 )
             } else {
                 quote!(
                     fn #method_name(
                         self: &Self,
                         msg: #message_type_name
-                    ) -> ::actix::prelude::Request<#type_name, #message_type_name>;
+                    ) -> #actix::prelude::Request<#type_name, #sent_type>;
+
+                    #many_method
                 )
             }
         })
@@ -123,6 +165,80 @@ fn expand_trait_methods(
         })
 }
 
+/// Expands a provided `<method>_many` trait method sending every message of an iterator and
+/// collecting the replies in order. At most `max_in_flight` replies are awaited concurrently
+/// when the option is set.
+///
+/// The method is generic, so it's left out of trait objects with `where Self: Sized`, and returns
+/// a boxed future rather than `impl Future`, which traits only allow since Rust 1.75.
+fn expand_many_method(options: &Options, handler_context: &HandlerContext) -> TokenStream2 {
+    let actix = &options.actix_path;
+    let message_type_name = &handler_context.message_type_name;
+    let method_name = addr_method_name(options, handler_context);
+    let many_method_name = format_ident!("{}_many", method_name);
+    let wait_for_slot = options.max_in_flight.map(|max_in_flight| {
+        quote!(
+            if pending.len() >= #max_in_flight {
+                if let ::std::option::Option::Some(request) = pending.pop_front() {
+                    results.push(request.await);
+                }
+            }
+        )
+    });
+
+    quote!(
+        fn #many_method_name<'a, I>(
+            self: &'a Self,
+            msgs: I,
+        ) -> ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<
+            Output = ::std::vec::Vec<
+                ::std::result::Result<<#message_type_name as #actix::Message>::Result, #actix::MailboxError>
+            >
+        > + 'a>>
+        where
+            Self: Sized,
+            I: ::std::iter::IntoIterator<Item = #message_type_name>,
+            I::IntoIter: 'a,
+        {
+            let mut msgs = ::std::iter::IntoIterator::into_iter(msgs);
+            ::std::boxed::Box::pin(async move {
+                let mut pending = ::std::collections::VecDeque::new();
+                let mut results = ::std::vec::Vec::new();
+                // A slot is freed before the next message is taken from the iterator
+                loop {
+                    #wait_for_slot
+                    match msgs.next() {
+                        ::std::option::Option::Some(msg) => pending.push_back(self.#method_name(msg)),
+                        ::std::option::Option::None => break,
+                    }
+                }
+                for request in pending {
+                    results.push(request.await);
+                }
+                results
+            })
+        }
+    )
+}
+
+/// The name of a handler's method on the `...Addr` trait, also used by the other client APIs.
+/// `#[handler(addr_name = "...")]` takes precedence over the impl level `addr_prefix` and
+/// `addr_suffix`.
+pub fn addr_method_name(options: &Options, handler_context: &HandlerContext) -> Ident {
+    match &handler_context.options.addr_name {
+        Some(addr_name) => {
+            format_ident!("{}", addr_name, span = handler_context.method_name.span())
+        }
+        None => format_ident!(
+            "{}{}{}",
+            options.addr_prefix.as_deref().unwrap_or(""),
+            handler_context.method_name,
+            options.addr_suffix.as_deref().unwrap_or(""),
+            span = handler_context.method_name.span()
+        ),
+    }
+}
+
 fn build_renamed_addr_type(trait_name: &Option<String>, type_name: &Type) -> Type {
     match type_name.clone() {
         Type::Path(path) => {
@@ -154,28 +270,30 @@ fn expand_addr_method(
     type_name: Type,
     handler_context: &HandlerContext,
 ) -> TokenStream2 {
-    let HandlerContext {
-        method_name,
-        message_type_name,
-        ..
-    } = handler_context;
+    let actix = &options.actix_path;
+    let message_type_name = &handler_context.message_type_name;
+    let method_name = addr_method_name(options, handler_context);
+    let sent_type = expand_sent_type(options, &type_name, message_type_name);
+    let sent_message = expand_sent_message(options, &type_name, quote!(msg));
+    // Errors about the method, e.g. when it isn't part of an implemented trait, point at the handler
+    let span = method_name.span();
 
     if options.use_recipient {
-        quote!(
+        quote_spanned!(span =>
             fn #method_name(
-                self: &Self,
+                &self,
                 msg: #message_type_name
-            ) -> ::actix::prelude::RecipientRequest<#message_type_name> {
-                self.clone().recipient().send(msg)
+            ) -> #actix::prelude::RecipientRequest<#sent_type> {
+                self.clone().recipient().send(#sent_message)
             }
         )
     } else {
-        quote!(
+        quote_spanned!(span =>
             fn #method_name(
-                self: &Self,
+                &self,
                 msg: #message_type_name
-            ) -> ::actix::prelude::Request<#type_name, #message_type_name> {
-                self.send(msg)
+            ) -> #actix::prelude::Request<#type_name, #sent_type> {
+                self.send(#sent_message)
             }
         )
     }
@@ -0,0 +1,193 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_ident;
+
+/// The field holding the actor's `...HandlerCache`, `cache` unless set with `cache_field`
+fn cache_field(options: &Options) -> Ident {
+    format_ident!("{}", options.cache_field.as_deref().unwrap_or("cache"))
+}
+
+/// Answers from the cache when `#[handler(cache(...))]` is set, storing fresh replies. Handlers
+/// marked `#[invalidates(...)]` clear the named caches once they've run.
+pub fn expand_cached_call(
+    options: &Options,
+    handler_context: &HandlerContext,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let field = cache_field(options);
+    let method_name = &handler_context.method_name;
+    let invalidates = &handler_context.options.invalidates;
+
+    let call = match &handler_context.options.cache {
+        Some(cache) => {
+            let key = match &cache.key {
+                Some(key) => quote!(::std::clone::Clone::clone(&msg.#key)),
+                None => quote!(::std::clone::Clone::clone(&msg)),
+            };
+            let ttl = match cache.ttl_secs {
+                Some(ttl_secs) => {
+                    quote!(::std::option::Option::Some(::std::time::Duration::from_secs(#ttl_secs)))
+                }
+                None => quote!(::std::option::Option::None),
+            };
+            quote!({
+                let __key = #key;
+                match self.#field.#method_name.get(&__key, #ttl) {
+                    ::std::option::Option::Some(cached) => cached,
+                    ::std::option::Option::None => {
+                        let __result = #call;
+                        self.#field
+                            .#method_name
+                            .insert(__key, ::std::clone::Clone::clone(&__result), #ttl);
+                        __result
+                    }
+                }
+            })
+        }
+        None => call,
+    };
+
+    if invalidates.is_empty() {
+        call
+    } else {
+        quote!({
+            let __result = #call;
+            #(self.#field.#invalidates.clear();)*
+            __result
+        })
+    }
+}
+
+/// Expands the `...HandlerCache` type holding the replies of every cached handler. Replies are
+/// timed with actix's clock, so expiry follows a paused clock in tests.
+pub fn expand_cache(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    let cached: Vec<&HandlerContext> = impl_context
+        .handlers
+        .iter()
+        .filter(|handler_context| handler_context.options.cache.is_some())
+        .collect();
+    if cached.is_empty() {
+        return TokenStream::new();
+    }
+
+    let actix = &options.actix_path;
+    let actor_ident = type_ident(&impl_context.type_name);
+    let cache_name = format_ident!("{}HandlerCache", actor_ident);
+    let entries_name = format_ident!("{}CacheEntries", actor_ident);
+    let fields = cached.iter().map(
+        |HandlerContext {
+             method_name,
+             result_type,
+             ..
+         }| quote!(#method_name: #entries_name<#result_type>),
+    );
+    let defaults = cached
+        .iter()
+        .map(|HandlerContext { method_name, .. }| quote!(#method_name: #entries_name::default()));
+    let clears = cached
+        .iter()
+        .map(|HandlerContext { method_name, .. }| quote!(self.#method_name.clear();));
+
+    TokenStream::from(quote!(
+        /// Replies of the handlers marked `#[handler(cache(...))]`
+        pub struct #cache_name {
+            #(#fields,)*
+        }
+
+        impl ::std::default::Default for #cache_name {
+            fn default() -> Self {
+                Self {
+                    #(#defaults,)*
+                }
+            }
+        }
+
+        impl #cache_name {
+            /// Drops every cached reply
+            #[allow(dead_code)]
+            pub fn clear(&mut self) {
+                #(#clears)*
+            }
+        }
+
+        /// Cached replies of one handler, bucketed by the hash of their key. Keys are kept to
+        /// compare them on lookup, so colliding hashes never return the wrong reply.
+        struct #entries_name<R> {
+            entries: ::std::collections::HashMap<
+                u64,
+                ::std::vec::Vec<(::std::boxed::Box<dyn ::std::any::Any>, #actix::clock::Instant, R)>,
+            >,
+            swept_at: #actix::clock::Instant,
+        }
+
+        impl<R> ::std::default::Default for #entries_name<R> {
+            fn default() -> Self {
+                Self {
+                    entries: ::std::collections::HashMap::new(),
+                    swept_at: #actix::clock::Instant::now(),
+                }
+            }
+        }
+
+        impl<R: ::std::clone::Clone> #entries_name<R> {
+            fn hash<K: ::std::hash::Hash>(key: &K) -> u64 {
+                let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+                ::std::hash::Hash::hash(key, &mut hasher);
+                ::std::hash::Hasher::finish(&hasher)
+            }
+
+            fn get<K: ::std::hash::Hash + ::std::cmp::Eq + 'static>(
+                &mut self,
+                key: &K,
+                ttl: ::std::option::Option<::std::time::Duration>,
+            ) -> ::std::option::Option<R> {
+                let bucket = self.entries.get_mut(&Self::hash(key))?;
+                if let ::std::option::Option::Some(ttl) = ttl {
+                    bucket.retain(|(_, cached_at, _)| cached_at.elapsed() < ttl);
+                }
+                bucket
+                    .iter()
+                    .find(|(cached_key, _, _)| cached_key.downcast_ref::<K>() == ::std::option::Option::Some(key))
+                    .map(|(_, _, reply)| ::std::clone::Clone::clone(reply))
+            }
+
+            /// Stores a reply, first dropping the expired ones if they haven't been swept for a
+            /// whole `ttl`, so keys that are never asked for again don't pile up
+            fn insert<K: ::std::hash::Hash + ::std::cmp::Eq + 'static>(
+                &mut self,
+                key: K,
+                reply: R,
+                ttl: ::std::option::Option<::std::time::Duration>,
+            ) {
+                if let ::std::option::Option::Some(ttl) = ttl {
+                    if self.swept_at.elapsed() >= ttl {
+                        for bucket in self.entries.values_mut() {
+                            bucket.retain(|(_, cached_at, _)| cached_at.elapsed() < ttl);
+                        }
+                        self.entries.retain(|_, bucket| !bucket.is_empty());
+                        self.swept_at = #actix::clock::Instant::now();
+                    }
+                }
+                let bucket = self.entries.entry(Self::hash(&key)).or_default();
+                bucket.retain(|(cached_key, _, _)| cached_key.downcast_ref::<K>() != ::std::option::Option::Some(&key));
+                bucket.push((::std::boxed::Box::new(key), #actix::clock::Instant::now(), reply));
+            }
+
+            fn clear(&mut self) {
+                self.entries.clear();
+            }
+
+            /// Cached replies, including expired ones that haven't been dropped yet
+            #[allow(dead_code)]
+            fn len(&self) -> usize {
+                self.entries.values().map(::std::vec::Vec::len).sum()
+            }
+        }
+    ))
+}
@@ -0,0 +1,72 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Type;
+
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::{CatchUnwind, Options};
+use crate::utils::type_name_literal;
+
+/// Wraps a handler `call` in `catch_unwind` when either the method or the impl block asks for
+/// it. Returns `call` untouched otherwise.
+///
+/// Stopping the actor only has an honest reply for handlers returning `()`; any other result would
+/// be made up, so those handlers are asked for a `#[handler(catch_unwind = "...")]` converter.
+pub fn expand_catch_unwind_call(
+    options: &Options,
+    ty: &Type,
+    handler_context: &HandlerContext,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let (catch_unwind, attr) = match &handler_context.options.catch_unwind {
+        Some(catch_unwind) => (catch_unwind.clone(), "#[handler(catch_unwind)]"),
+        None if options.catch_unwind => (CatchUnwind::Stop, "#[actix_handler(catch_unwind)]"),
+        None => return call,
+    };
+    if matches!(catch_unwind, CatchUnwind::Stop) && !returns_unit(&handler_context.result_type) {
+        return syn::Error::new(
+            handler_context.result_type.span(),
+            format!(
+                "'{}' returns a value, which {} can't answer with on panic; convert the panic \
+                 with #[handler(catch_unwind = \"path::to::convert\")] instead",
+                handler_context.method_name, attr
+            ),
+        )
+        .to_compile_error();
+    }
+
+    let actix = &options.actix_path;
+    let actor_name = type_name_literal(ty);
+    let method_name = handler_context.method_name.to_string();
+    let report = options.panic_hook.as_ref().map(|panic_hook| {
+        quote!(
+            #panic_hook(#actor_name, #method_name, &*__panic);
+        )
+    });
+    let recover = match catch_unwind {
+        CatchUnwind::Stop => quote!({
+            #actix::ActorContext::stop(ctx);
+        }),
+        CatchUnwind::Convert(convert) => quote!(#convert(__panic)),
+    };
+
+    quote!(
+        match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| #call)) {
+            ::std::result::Result::Ok(__result) => __result,
+            ::std::result::Result::Err(__panic) => {
+                #report
+                #recover
+            }
+        }
+    )
+}
+
+/// Whether a handler returns `()`, either written out or by leaving out the return type, in which
+/// case the result type is read off the message as `<Message as actix::Message>::Result`
+fn returns_unit(result_type: &Type) -> bool {
+    match result_type {
+        Type::Tuple(tuple) => tuple.elems.is_empty(),
+        Type::Path(path) => path.qself.is_some(),
+        _ => false,
+    }
+}
@@ -0,0 +1,94 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_ident;
+
+/// Expands the `ctx_ext` option: a `...CtxExt` trait for the actor's context with helpers sending
+/// the handled messages to the actor itself. It's named after `trait_name` when set, so several
+/// blocks of one actor can each have their own.
+pub fn expand_ctx_ext(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    if !options.ctx_ext {
+        return TokenStream::new();
+    }
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+    let trait_name = match &options.trait_name {
+        Some(trait_name) => format_ident!("{}CtxExt", trait_name),
+        None => format_ident!("{}CtxExt", type_ident(type_name)),
+    };
+
+    let (trait_fns, impl_fns): (Vec<TokenStream2>, Vec<TokenStream2>) = impl_context
+        .handlers
+        .iter()
+        .map(|handler_context| {
+            let HandlerContext {
+                method_name,
+                message_type_name,
+                ..
+            } = handler_context;
+            let notify = format_ident!("notify_{}", method_name);
+            let notify_later = format_ident!("notify_{}_later", method_name);
+            let run_interval = format_ident!("run_{}_interval", method_name);
+
+            let trait_fns = quote!(
+                fn #notify(self: &mut Self, msg: #message_type_name);
+
+                fn #notify_later(
+                    self: &mut Self,
+                    msg: #message_type_name,
+                    after: ::std::time::Duration,
+                ) -> #actix::SpawnHandle;
+
+                fn #run_interval<F>(
+                    self: &mut Self,
+                    interval: ::std::time::Duration,
+                    f: F,
+                ) -> #actix::SpawnHandle
+                where
+                    F: FnMut(&mut #type_name) -> #message_type_name + 'static;
+            );
+            let impl_fns = quote!(
+                fn #notify(self: &mut Self, msg: #message_type_name) {
+                    #actix::AsyncContext::notify(self, msg)
+                }
+
+                fn #notify_later(
+                    self: &mut Self,
+                    msg: #message_type_name,
+                    after: ::std::time::Duration,
+                ) -> #actix::SpawnHandle {
+                    #actix::AsyncContext::notify_later(self, msg, after)
+                }
+
+                fn #run_interval<F>(
+                    self: &mut Self,
+                    interval: ::std::time::Duration,
+                    mut f: F,
+                ) -> #actix::SpawnHandle
+                where
+                    F: FnMut(&mut #type_name) -> #message_type_name + 'static,
+                {
+                    #actix::AsyncContext::run_interval(self, interval, move |act, ctx| {
+                        let msg = f(act);
+                        #actix::AsyncContext::notify(ctx, msg)
+                    })
+                }
+            );
+            (trait_fns, impl_fns)
+        })
+        .unzip();
+
+    TokenStream::from(quote!(
+        trait #trait_name {
+            #(#trait_fns)*
+        }
+
+        impl #trait_name for #actix::Context<#type_name> {
+            #(#impl_fns)*
+        }
+    ))
+}
@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+
+/// Checks a handler's guard before `call`, replying with the guard's error converted into the
+/// handler's `Result` when it fails. The method's `#[handler(guard = "...")]` takes precedence
+/// over the impl level `guard`, and `#[handler(no_guard)]` opts out of the latter.
+pub fn expand_guarded_call(
+    options: &Options,
+    handler_context: &HandlerContext,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let guard = match (&handler_context.options.guard, &options.guard) {
+        (Some(guard), _) => guard,
+        (None, Some(_)) if handler_context.options.no_guard => return call,
+        (None, Some(guard)) => guard,
+        (None, None) => return call,
+    };
+
+    quote!(
+        match #guard(self, &msg) {
+            ::std::result::Result::Ok(()) => #call,
+            ::std::result::Result::Err(err) => {
+                ::std::result::Result::Err(::std::convert::From::from(err))
+            }
+        }
+    )
+}
@@ -0,0 +1,47 @@
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_name_literal;
+
+/// Expands a `HANDLERS` associated const describing every handler, built out of the
+/// user-supplied `handler_info` struct.
+pub fn expand_handler_info(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    let handler_info = match &options.handler_info {
+        Some(handler_info) => handler_info,
+        None => return TokenStream::new(),
+    };
+    let type_name = &impl_context.type_name;
+
+    let infos = impl_context.handlers.iter().map(
+        |HandlerContext {
+             method_name,
+             message_type_name,
+             result_type,
+             ..
+         }| {
+            let method = method_name.to_string();
+            let message = type_name_literal(message_type_name);
+            let result = type_name_literal(result_type);
+            quote!(
+                #handler_info {
+                    method: #method,
+                    message: #message,
+                    result: #result,
+                    message_type_id: ::std::any::TypeId::of::<#message_type_name>,
+                    result_type_id: ::std::any::TypeId::of::<#result_type>,
+                }
+            )
+        },
+    );
+
+    TokenStream::from(quote!(
+        impl #type_name {
+            /// The messages this actor handles
+            #[allow(dead_code)]
+            pub const HANDLERS: &'static [#handler_info] = &[#(#infos),*];
+        }
+    ))
+}
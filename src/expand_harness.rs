@@ -0,0 +1,59 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_ident;
+
+/// Expands a `...Harness` owning the actor and a detached context, with a method per handler
+/// calling it synchronously, so handlers can be tested without a running `System`.
+pub fn expand_harness(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    if !options.harness {
+        return TokenStream::new();
+    }
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+    let harness_name = format_ident!("{}Harness", type_ident(type_name));
+
+    let methods = impl_context.handlers.iter().map(
+        |HandlerContext {
+             method_name,
+             message_type_name,
+             result_type,
+             ..
+         }| {
+            quote!(
+                pub fn #method_name(&mut self, msg: #message_type_name) -> #result_type {
+                    <#type_name as #actix::Handler<#message_type_name>>::handle(
+                        &mut self.actor,
+                        msg,
+                        &mut self.ctx,
+                    )
+                }
+            )
+        },
+    );
+
+    TokenStream::from(quote!(
+        /// Runs the handlers of an actor directly, without starting it
+        #[allow(dead_code)]
+        struct #harness_name {
+            pub actor: #type_name,
+            pub ctx: #actix::Context<#type_name>,
+        }
+
+        #[allow(dead_code)]
+        impl #harness_name {
+            pub fn new(actor: #type_name) -> Self {
+                let (_, receiver) = #actix::dev::channel::channel(16);
+                Self {
+                    actor,
+                    ctx: #actix::Context::with_receiver(receiver),
+                }
+            }
+
+            #(#methods)*
+        }
+    ))
+}
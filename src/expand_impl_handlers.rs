@@ -2,26 +2,89 @@ use proc_macro::TokenStream;
 use syn::{ImplItem, ImplItemMethod, ItemImpl, Type};
 
 use crate::expand_addr::{expand_addr_trait, ImplContext};
+use crate::expand_cache::expand_cache;
+use crate::expand_ctx_ext::expand_ctx_ext;
+use crate::expand_handler_info::expand_handler_info;
+use crate::expand_harness::expand_harness;
+use crate::expand_journal::expand_journal;
+use crate::expand_json_rpc::expand_json_rpc;
+use crate::expand_message_enum::expand_message_enum;
 use crate::expand_method_handlers::{
     expand_handler_context, parse_handler_context, HandlerContext, SignatureValidationError,
 };
-use crate::options::Options;
-use crate::utils::compilation_error;
+use crate::expand_protocol::{expand_protocol_impl, validate_implemented_traits};
+use crate::expand_remote::expand_remote;
+use crate::expand_timers::{expand_timers, parse_timers, TimerContext};
+use crate::expand_trace_context::expand_trace_context;
+use crate::options::{strip_impl_method_attrs, take_handler_options, HandlerOptions, Options};
+use crate::utils::{compilation_error, push_error};
 
 pub fn expand_item_impl(options: Options, item_impl: &mut ItemImpl) -> TokenStream {
+    if options.metrics && !cfg!(feature = "metrics") {
+        return compilation_error(
+            "#[actix_handler(metrics)] requires the `metrics` feature of actix-handler-macro",
+        );
+    }
+    if options.remote && !cfg!(feature = "remote") {
+        return compilation_error(
+            "#[actix_handler(remote)] requires the `remote` feature of actix-handler-macro",
+        );
+    }
+
     let ty = *item_impl.self_ty.clone();
 
+    let timers = match parse_timers(item_impl) {
+        Ok(timers) => timers,
+        Err(err) => {
+            strip_impl_method_attrs(item_impl);
+            return TokenStream::from(err.to_compile_error());
+        }
+    };
+    let timers_output = expand_timers(&options, &ty, &timers);
+
     let handler_contexts: Vec<(
         &ImplItemMethod,
         Result<HandlerContext, SignatureValidationError>,
-    )> = parse_method_handlers(item_impl);
-    let handlers_output: TokenStream = expand_method_handlers(&ty, &handler_contexts);
+    )> = match parse_method_handlers(&options, item_impl, &timers) {
+        Ok(handler_contexts) => handler_contexts,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    let handlers_output: TokenStream = expand_method_handlers(&options, &ty, &handler_contexts);
 
     let impl_context = build_impl_context(ty, handler_contexts);
-    let addr_output = expand_addr_trait(options, impl_context);
+    let message_enum_output = expand_message_enum(&options, &impl_context);
+    let remote_output = expand_remote(&options, &impl_context);
+    let json_rpc_output = expand_json_rpc(&options, &impl_context);
+    let ctx_ext_output = expand_ctx_ext(&options, &impl_context);
+    let handler_info_output = expand_handler_info(&options, &impl_context);
+    if let Err(err) = validate_implemented_traits(&options, &impl_context) {
+        return TokenStream::from(err.to_compile_error());
+    }
+    let protocol_output = expand_protocol_impl(&options, &impl_context);
+    let trace_context_output = expand_trace_context(&options, &impl_context);
+    let cache_output = expand_cache(&options, &impl_context);
+    let harness_output = expand_harness(&options, &impl_context);
+    let journal_output = expand_journal(&options, &impl_context);
+    // Blocks implementing a protocol get their client methods from the protocol's trait
+    let addr_output = if !options.implements.is_empty() && !options.no_trait_decl {
+        TokenStream::new()
+    } else {
+        expand_addr_trait(options, impl_context)
+    };
 
     let mut output = TokenStream::new();
     output.extend(handlers_output);
+    output.extend(timers_output);
+    output.extend(message_enum_output);
+    output.extend(remote_output);
+    output.extend(json_rpc_output);
+    output.extend(ctx_ext_output);
+    output.extend(handler_info_output);
+    output.extend(protocol_output);
+    output.extend(trace_context_output);
+    output.extend(cache_output);
+    output.extend(harness_output);
+    output.extend(journal_output);
     output.extend(addr_output);
     output
 }
@@ -48,6 +111,7 @@ fn build_impl_context(
 }
 
 fn expand_method_handlers(
+    options: &Options,
     ty: &Type,
     handler_contexts: &[(
         &ImplItemMethod,
@@ -57,7 +121,7 @@ fn expand_method_handlers(
     handler_contexts
         .iter()
         .map(|(method, result)| match result {
-            Ok(handler_context) => expand_handler_context(&ty, &handler_context),
+            Ok(handler_context) => expand_handler_context(options, ty, handler_context),
             Err(err) => handle_signature_error(&method.sig.ident.to_string(), err.clone()),
         })
         .fold(TokenStream::new(), |mut m, i| {
@@ -66,24 +130,58 @@ fn expand_method_handlers(
         })
 }
 
-fn parse_method_handlers(
-    item_impl: &mut ItemImpl,
-) -> Vec<(
-    &ImplItemMethod,
-    Result<HandlerContext, SignatureValidationError>,
-)> {
-    item_impl
+fn parse_method_handlers<'a>(
+    options: &Options,
+    item_impl: &'a mut ItemImpl,
+    timers: &[TimerContext],
+) -> syn::Result<
+    Vec<(
+        &'a ImplItemMethod,
+        Result<HandlerContext, SignatureValidationError>,
+    )>,
+> {
+    let mut errors = None;
+    let mut handler_options: Vec<HandlerOptions> = Vec::new();
+    for item in &mut item_impl.items {
+        match item {
+            ImplItem::Method(method) if !is_timer(timers, method) => {
+                match take_handler_options(&mut method.attrs) {
+                    Ok(options) => handler_options.push(options),
+                    Err(err) => push_error(&mut errors, err),
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    Ok(item_impl
         .items
         .iter()
-        .filter_map(|item| {
-            if let ImplItem::Method(method) = item {
-                Some(method)
-            } else {
-                None
-            }
+        .filter_map(|item| match item {
+            ImplItem::Method(method) if !is_timer(timers, method) => Some(method),
+            _ => None,
         })
-        .map(|method| (method, parse_handler_context(method)))
-        .collect()
+        .zip(handler_options)
+        .map(|(method, handler_options)| {
+            let handler_context =
+                parse_handler_context(method, &options.actix_path).map(|handler_context| {
+                    HandlerContext {
+                        options: handler_options,
+                        ..handler_context
+                    }
+                });
+            (method, handler_context)
+        })
+        .collect())
+}
+
+fn is_timer(timers: &[TimerContext], method: &ImplItemMethod) -> bool {
+    timers
+        .iter()
+        .any(|timer| timer.method_name == method.sig.ident)
 }
 
 fn handle_signature_error(method_name: &str, err: SignatureValidationError) -> TokenStream {
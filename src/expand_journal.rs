@@ -0,0 +1,193 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Ident, Type};
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::{compilation_error, type_ident, type_name_literal};
+
+/// The field holding the actor's `Option<Box<dyn ...Journal>>`, `journal` unless set with
+/// `journal_field`
+fn journal_field(options: &Options) -> Ident {
+    format_ident!("{}", options.journal_field.as_deref().unwrap_or("journal"))
+}
+
+fn journal_trait_name(type_name: &Type) -> Ident {
+    format_ident!("{}Journal", type_ident(type_name))
+}
+
+/// Appends the message to the actor's journal before `#[handler(persist)]` handlers run. Entries
+/// are `[method, message]` JSON arrays, so replaying doesn't depend on the journal's format.
+///
+/// A message that can't be recorded is reported to `journal_error` and still handled, so its
+/// request is answered, but the actor is stopped so no further state goes unrecorded.
+pub fn expand_persisted_call(
+    options: &Options,
+    ty: &Type,
+    handler_context: &HandlerContext,
+    call: TokenStream2,
+) -> TokenStream2 {
+    if !options.journal || !handler_context.options.persist {
+        return call;
+    }
+    let actix = &options.actix_path;
+    let field = journal_field(options);
+    let journal_trait = journal_trait_name(ty);
+    let actor_name = type_name_literal(ty);
+    let method_name = handler_context.method_name.to_string();
+    let report = options.journal_error.as_ref().map(|journal_error| {
+        quote!(
+            #journal_error(#actor_name, #method_name, &error);
+        )
+    });
+
+    quote!({
+        if let ::std::option::Option::Some(journal) = self.#field.as_mut() {
+            let appended = ::serde_json::to_string(&(#method_name, &msg))
+                .map_err(::std::io::Error::from)
+                .and_then(|entry| #journal_trait::append(&mut **journal, &entry));
+            if let ::std::result::Result::Err(error) = appended {
+                #report
+                #actix::ActorContext::stop(ctx);
+            }
+        }
+        #call
+    })
+}
+
+/// Expands the `journal` option: a `...Journal` trait with in-memory and file-backed
+/// implementations, and a `replay` constructor re-running the persisted handlers.
+pub fn expand_journal(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    let persisted: Vec<&HandlerContext> = impl_context
+        .handlers
+        .iter()
+        .filter(|handler_context| handler_context.options.persist)
+        .collect();
+    if !options.journal {
+        return if persisted.is_empty() {
+            TokenStream::new()
+        } else {
+            compilation_error("#[handler(persist)] requires #[actix_handler(journal)]")
+        };
+    }
+
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+    let actor_ident = type_ident(type_name);
+    let journal_trait = journal_trait_name(type_name);
+    let memory_journal = format_ident!("{}MemoryJournal", actor_ident);
+    let file_journal = format_ident!("{}FileJournal", actor_ident);
+    let field = journal_field(options);
+
+    let replay_arms = persisted.iter().map(|handler_context| {
+        let HandlerContext {
+            method_name,
+            message_type_name,
+            ..
+        } = handler_context;
+        let method_name_str = method_name.to_string();
+        quote!(
+            #method_name_str => {
+                let msg: #message_type_name = ::serde_json::from_value(message)?;
+                let _ = actor.#method_name(msg, &mut ctx);
+            }
+        )
+    });
+
+    TokenStream::from(quote!(
+        /// Where the persisted messages of an actor are appended, one serialized entry at a time
+        #[allow(dead_code)]
+        pub trait #journal_trait {
+            fn append(&mut self, entry: &str) -> ::std::io::Result<()>;
+
+            /// Every entry appended so far, oldest first
+            fn entries(&self) -> ::std::io::Result<::std::vec::Vec<::std::string::String>>;
+        }
+
+        /// Keeps entries in memory. Clones share the same entries.
+        #[derive(Clone, Default)]
+        #[allow(dead_code)]
+        pub struct #memory_journal {
+            entries: ::std::sync::Arc<::std::sync::Mutex<::std::vec::Vec<::std::string::String>>>,
+        }
+
+        impl #journal_trait for #memory_journal {
+            fn append(&mut self, entry: &str) -> ::std::io::Result<()> {
+                self.entries.lock().unwrap().push(entry.to_string());
+                ::std::result::Result::Ok(())
+            }
+
+            fn entries(&self) -> ::std::io::Result<::std::vec::Vec<::std::string::String>> {
+                ::std::result::Result::Ok(self.entries.lock().unwrap().clone())
+            }
+        }
+
+        /// Appends entries to a file, one per line
+        #[allow(dead_code)]
+        pub struct #file_journal {
+            path: ::std::path::PathBuf,
+            file: ::std::fs::File,
+        }
+
+        #[allow(dead_code)]
+        impl #file_journal {
+            /// Opens the journal at `path`, creating it if needed
+            pub fn open(path: impl ::std::convert::AsRef<::std::path::Path>) -> ::std::io::Result<Self> {
+                let path = path.as_ref().to_path_buf();
+                let file = ::std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)?;
+                ::std::result::Result::Ok(Self { path, file })
+            }
+        }
+
+        impl #journal_trait for #file_journal {
+            fn append(&mut self, entry: &str) -> ::std::io::Result<()> {
+                use ::std::io::Write;
+                self.file.write_all(entry.as_bytes())?;
+                self.file.write_all(b"\n")?;
+                self.file.flush()
+            }
+
+            fn entries(&self) -> ::std::io::Result<::std::vec::Vec<::std::string::String>> {
+                ::std::result::Result::Ok(
+                    ::std::fs::read_to_string(&self.path)?
+                        .lines()
+                        .map(::std::string::ToString::to_string)
+                        .collect(),
+                )
+            }
+        }
+
+        impl #type_name {
+            /// Rebuilds the actor from its default state by re-running the persisted handlers
+            /// over every entry in `journal`, which then records the messages handled from here on
+            #[allow(dead_code)]
+            pub fn replay(journal: impl #journal_trait + 'static) -> ::std::io::Result<Self> {
+                let mut actor: Self = ::std::default::Default::default();
+                let (_, receiver) = #actix::dev::channel::channel(16);
+                let mut ctx: #actix::Context<Self> = #actix::Context::with_receiver(receiver);
+
+                for entry in #journal_trait::entries(&journal)? {
+                    let (method, message): (::std::string::String, ::serde_json::Value) =
+                        ::serde_json::from_str(&entry)?;
+                    match method.as_str() {
+                        #(#replay_arms)*
+                        _ => {
+                            return ::std::result::Result::Err(::std::io::Error::new(
+                                ::std::io::ErrorKind::InvalidData,
+                                ::std::format!("no persisted handler named `{}`", method),
+                            ))
+                        }
+                    }
+                }
+
+                actor.#field = ::std::option::Option::Some(::std::boxed::Box::new(journal));
+                ::std::result::Result::Ok(actor)
+            }
+        }
+    ))
+}
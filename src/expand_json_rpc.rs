@@ -0,0 +1,108 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+
+use crate::expand_addr::{addr_method_name, ImplContext};
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_ident;
+
+/// Expands the `json_rpc` option: an `...JsonRpc` trait implemented for the actor's `Addr`
+/// routing JSON-RPC calls to handlers by method name.
+pub fn expand_json_rpc(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    if !options.json_rpc {
+        return TokenStream::new();
+    }
+
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+    let prefix = type_ident(type_name);
+    let trait_name = format_ident!("{}JsonRpc", prefix);
+    let error_name = format_ident!("{}JsonRpcError", prefix);
+
+    // Internal messages hidden with `no_addr` can't be called from outside either
+    let handlers = impl_context
+        .handlers
+        .iter()
+        .filter(|handler_context| !handler_context.options.no_addr);
+    let dispatch_arms = handlers.map(|handler_context| {
+        let HandlerContext {
+            message_type_name, ..
+        } = handler_context;
+        let method_name = addr_method_name(options, handler_context).to_string();
+        quote!(
+            #method_name => {
+                let msg: #message_type_name =
+                    ::serde_json::from_value(params).map_err(#error_name::InvalidParams)?;
+                let result = addr.send(msg).await.map_err(#error_name::Mailbox)?;
+                ::serde_json::to_value(result).map_err(#error_name::InvalidResult)
+            }
+        )
+    });
+
+    let output: TokenStream2 = quote!(
+        #[derive(Debug)]
+        enum #error_name {
+            MethodNotFound(::std::string::String),
+            InvalidParams(::serde_json::Error),
+            InvalidResult(::serde_json::Error),
+            Mailbox(#actix::MailboxError),
+        }
+
+        impl #error_name {
+            /// The JSON-RPC 2.0 error code for this error
+            #[allow(dead_code)]
+            fn code(self: &Self) -> i64 {
+                match self {
+                    #error_name::MethodNotFound(_) => -32601,
+                    #error_name::InvalidParams(_) => -32602,
+                    #error_name::InvalidResult(_) | #error_name::Mailbox(_) => -32603,
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    #error_name::MethodNotFound(method) => write!(f, "method not found: {}", method),
+                    #error_name::InvalidParams(err) => write!(f, "invalid params: {}", err),
+                    #error_name::InvalidResult(err) => write!(f, "result can't be serialized: {}", err),
+                    #error_name::Mailbox(err) => write!(f, "actor failed: {}", err),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        trait #trait_name {
+            fn dispatch_json(
+                self: &Self,
+                method: &str,
+                params: ::serde_json::Value,
+            ) -> ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<
+                Output = ::std::result::Result<::serde_json::Value, #error_name>
+            >>>;
+        }
+
+        impl #trait_name for #actix::Addr<#type_name> {
+            fn dispatch_json(
+                self: &Self,
+                method: &str,
+                params: ::serde_json::Value,
+            ) -> ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<
+                Output = ::std::result::Result<::serde_json::Value, #error_name>
+            >>> {
+                let addr = self.clone();
+                let method = method.to_string();
+                ::std::boxed::Box::pin(async move {
+                    match method.as_str() {
+                        #(#dispatch_arms)*
+                        _ => ::std::result::Result::Err(#error_name::MethodNotFound(method)),
+                    }
+                })
+            }
+        }
+    );
+
+    TokenStream::from(output)
+}
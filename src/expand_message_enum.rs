@@ -0,0 +1,95 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::actix_version::expand_message_response_impl;
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::to_camel_case;
+
+/// Expands the `message_enum` option: an enum with one variant per handled message, a matching
+/// enum of results and a `Handler` dispatching each variant to the existing handlers.
+pub fn expand_message_enum(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    let enum_name = match &options.message_enum {
+        Some(enum_name) => format_ident!("{}", enum_name),
+        None => return TokenStream::new(),
+    };
+    let result_enum_name = format_ident!("{}Result", enum_name);
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+
+    let variants: Vec<(Ident, &HandlerContext)> = impl_context
+        .handlers
+        .iter()
+        .map(|handler_context| (message_variant_name(handler_context), handler_context))
+        .collect();
+
+    let message_variants = variants.iter().map(|(variant, handler_context)| {
+        let message_type_name = &handler_context.message_type_name;
+        quote!(#variant(#message_type_name))
+    });
+    let result_variants = variants.iter().map(|(variant, handler_context)| {
+        let result_type = &handler_context.result_type;
+        quote!(#variant(#result_type))
+    });
+    let from_impls = variants.iter().map(|(variant, handler_context)| {
+        let message_type_name = &handler_context.message_type_name;
+        quote!(
+            impl ::std::convert::From<#message_type_name> for #enum_name {
+                fn from(msg: #message_type_name) -> Self {
+                    #enum_name::#variant(msg)
+                }
+            }
+        )
+    });
+    let dispatch_arms = variants.iter().map(|(variant, handler_context)| {
+        let message_type_name = &handler_context.message_type_name;
+        quote!(
+            #enum_name::#variant(msg) => #result_enum_name::#variant(
+                <Self as #actix::Handler<#message_type_name>>::handle(self, msg, ctx)
+            ),
+        )
+    });
+
+    let message_response_impl = expand_message_response_impl(actix, &result_enum_name);
+
+    let output: TokenStream2 = quote!(
+        enum #enum_name {
+            #(#message_variants,)*
+        }
+
+        #(#from_impls)*
+
+        enum #result_enum_name {
+            #(#result_variants,)*
+        }
+
+        impl #actix::Message for #enum_name {
+            type Result = #result_enum_name;
+        }
+
+        #message_response_impl
+
+        impl #actix::Handler<#enum_name> for #type_name {
+            type Result = #result_enum_name;
+
+            fn handle(self: &mut Self, msg: #enum_name, ctx: &mut #actix::Context<Self>) -> Self::Result {
+                match msg {
+                    #(#dispatch_arms)*
+                }
+            }
+        }
+    );
+
+    TokenStream::from(output)
+}
+
+/// The variant used for a handler in the generated enums, e.g. `say_hello` becomes `SayHello`
+pub fn message_variant_name(handler_context: &HandlerContext) -> Ident {
+    format_ident!(
+        "{}",
+        to_camel_case(&handler_context.method_name.to_string())
+    )
+}
@@ -1,21 +1,40 @@
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
 use quote::quote;
-use syn::{parse_quote, FnArg, ImplItemMethod, ReturnType, Type};
+use syn::{parse_quote, FnArg, ImplItemMethod, Path, ReturnType, Type};
 
-pub fn expand_handler_context(ty: &Type, handler_context: &HandlerContext) -> TokenStream {
+use crate::expand_cache::expand_cached_call;
+use crate::expand_catch_unwind::expand_catch_unwind_call;
+use crate::expand_guard::expand_guarded_call;
+use crate::expand_journal::expand_persisted_call;
+use crate::expand_observer::expand_observed_call;
+use crate::options::{HandlerOptions, Options};
+
+pub fn expand_handler_context(
+    options: &Options,
+    ty: &Type,
+    handler_context: &HandlerContext,
+) -> TokenStream {
     let HandlerContext {
         message_type_name,
         method_name,
         result_type,
+        ..
     } = handler_context;
+    let actix = &options.actix_path;
+    let call = quote!(self.#method_name(msg, ctx));
+    let call = expand_persisted_call(options, ty, handler_context, call);
+    let call = expand_cached_call(options, handler_context, call);
+    let call = expand_guarded_call(options, handler_context, call);
+    let call = expand_catch_unwind_call(options, ty, handler_context, call);
+    let call = expand_observed_call(options, ty, handler_context, call);
 
     TokenStream::from(quote!(
-        impl actix::Handler<#message_type_name> for #ty {
+        impl #actix::Handler<#message_type_name> for #ty {
             type Result = #result_type;
 
-            fn handle(self: &mut Self, msg: #message_type_name, ctx: &mut actix::Context<Self>) -> Self::Result {
-                self.#method_name(msg, ctx)
+            fn handle(self: &mut Self, msg: #message_type_name, ctx: &mut #actix::Context<Self>) -> Self::Result {
+                #call
             }
         }
     ))
@@ -26,6 +45,7 @@ pub struct HandlerContext {
     pub(crate) message_type_name: Type,
     pub(crate) method_name: Ident,
     pub(crate) result_type: Type,
+    pub(crate) options: HandlerOptions,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -36,6 +56,7 @@ pub enum SignatureValidationError {
 
 pub fn parse_handler_context(
     method: &ImplItemMethod,
+    actix: &Path,
 ) -> Result<HandlerContext, SignatureValidationError> {
     use SignatureValidationError::*;
 
@@ -54,7 +75,9 @@ pub fn parse_handler_context(
         FnArg::Typed(message_arg) => {
             let message_type_name = *message_arg.ty;
             let result_type: Type = match signature.output {
-                ReturnType::Default => parse_quote!(<#message_type_name as actix::Message>::Result),
+                ReturnType::Default => {
+                    parse_quote!(<#message_type_name as #actix::Message>::Result)
+                }
                 ReturnType::Type(_, r_type) => (*r_type).clone(),
             };
 
@@ -62,6 +85,7 @@ pub fn parse_handler_context(
                 method_name,
                 message_type_name,
                 result_type,
+                options: HandlerOptions::default(),
             })
         }
         _ => Err(UnexpectedArguments),
@@ -80,8 +104,8 @@ mod tests {
         let input: ImplItemMethod = parse_quote! {
             fn greet(&self, _message: Greeting, _ctx: &Example::Context) {}
         };
-        let handler_context = parse_handler_context(&input);
-        assert_eq!(handler_context.is_ok(), true);
+        let handler_context = parse_handler_context(&input, &parse_quote!(::actix));
+        assert!(handler_context.is_ok());
         let handler_context = handler_context.unwrap_or_else(|_| panic!("Expected HandlerContext"));
         assert_eq!(
             format!("{:?}", handler_context.message_type_name),
@@ -95,8 +119,8 @@ mod tests {
         let input: ImplItemMethod = parse_quote! {
             fn greet(&self, _message: Greeting) {}
         };
-        let result = parse_handler_context(&input);
-        assert_eq!(result.is_ok(), false);
+        let result = parse_handler_context(&input, &parse_quote!(::actix));
+        assert!(result.is_err());
         let err = result.err().unwrap();
         assert_eq!(err, SignatureValidationError::WrongArity);
     }
@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::Type;
+
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_name_literal;
+
+/// Wraps a handler `call` so that the configured observer and metrics backend see every
+/// invocation. Returns `call` untouched when neither is enabled.
+pub fn expand_observed_call(
+    options: &Options,
+    ty: &Type,
+    handler_context: &HandlerContext,
+    call: TokenStream2,
+) -> TokenStream2 {
+    if options.observer.is_none() && !options.metrics {
+        return call;
+    }
+
+    let actor_name = type_name_literal(ty);
+    let method_name = handler_context.method_name.to_string();
+    let message_type_name = type_name_literal(&handler_context.message_type_name);
+
+    let before = options.observer.as_ref().map(|observer| {
+        quote!(
+            #observer::before_handle(#actor_name, #method_name, #message_type_name);
+        )
+    });
+    let after = options.observer.as_ref().map(|observer| {
+        quote!(
+            #observer::after_handle(#actor_name, #method_name, #message_type_name, __elapsed);
+        )
+    });
+    let metrics = if options.metrics {
+        Some(quote!(
+            ::metrics::counter!(
+                "actix_handler_calls_total",
+                "actor" => #actor_name,
+                "method" => #method_name,
+                "message" => #message_type_name
+            )
+            .increment(1);
+            ::metrics::histogram!(
+                "actix_handler_duration_seconds",
+                "actor" => #actor_name,
+                "method" => #method_name,
+                "message" => #message_type_name
+            )
+            .record(__elapsed.as_secs_f64());
+        ))
+    } else {
+        None
+    };
+
+    quote!({
+        #before
+        let __started_at = ::std::time::Instant::now();
+        let __result = #call;
+        let __elapsed = __started_at.elapsed();
+        #after
+        #metrics
+        __result
+    })
+}
@@ -0,0 +1,287 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{FnArg, Ident, ItemTrait, Pat, Path, ReturnType, TraitItem, Type};
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::{compilation_error, same_path, to_camel_case};
+
+struct ProtocolMethod {
+    method_name: Ident,
+    message_name: Ident,
+    args: Vec<(Ident, Type)>,
+    result_type: Type,
+}
+
+/// Expands an `#[actix_protocol]` trait into its message structs, a client trait implemented for
+/// every `Addr<A>` handling them and a `...Handler` trait for the actor side.
+pub fn expand_protocol(options: &Options, item_trait: &ItemTrait) -> TokenStream {
+    let actix = &options.actix_path;
+    let vis = &item_trait.vis;
+    let trait_name = &item_trait.ident;
+    let handler_trait_name = format_ident!("{}Handler", trait_name);
+
+    let mut methods = vec![];
+    for item in &item_trait.items {
+        let method = match item {
+            TraitItem::Method(method) => method,
+            _ => return compilation_error("#[actix_protocol] traits may only declare methods"),
+        };
+        match parse_protocol_method(method) {
+            Some(method) => methods.push(method),
+            None => {
+                return compilation_error(format!(
+                    "#[actix_protocol] method '{}' must take '&self' and named arguments",
+                    method.sig.ident
+                ))
+            }
+        }
+    }
+
+    let messages = methods.iter().map(
+        |ProtocolMethod {
+             message_name,
+             args,
+             result_type,
+             ..
+         }| {
+            let fields = args.iter().map(|(name, ty)| quote!(pub #name: #ty));
+            quote!(
+                #vis struct #message_name {
+                    #(#fields,)*
+                }
+
+                impl #actix::Message for #message_name {
+                    type Result = #result_type;
+                }
+            )
+        },
+    );
+
+    let client_fns = methods.iter().map(
+        |ProtocolMethod {
+             method_name,
+             args,
+             result_type,
+             ..
+         }| {
+            let args = args.iter().map(|(name, ty)| quote!(#name: #ty));
+            quote!(
+                fn #method_name(
+                    self: &Self,
+                    #(#args),*
+                ) -> ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<
+                    Output = ::std::result::Result<#result_type, #actix::MailboxError>
+                >>>;
+            )
+        },
+    );
+
+    let client_impls = methods.iter().map(
+        |ProtocolMethod {
+             method_name,
+             message_name,
+             args,
+             result_type,
+         }| {
+            let arg_names: Vec<_> = args.iter().map(|(name, _)| name).collect();
+            let args = args.iter().map(|(name, ty)| quote!(#name: #ty));
+            quote!(
+                fn #method_name(
+                    self: &Self,
+                    #(#args),*
+                ) -> ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<
+                    Output = ::std::result::Result<#result_type, #actix::MailboxError>
+                >>> {
+                    ::std::boxed::Box::pin(self.send(#message_name { #(#arg_names),* }))
+                }
+            )
+        },
+    );
+
+    let client_bounds = methods.iter().map(|ProtocolMethod { message_name, .. }| {
+        quote!(
+            A: #actix::Handler<#message_name>,
+            A::Context: #actix::dev::ToEnvelope<A, #message_name>,
+        )
+    });
+
+    let handler_fns = methods.iter().map(
+        |ProtocolMethod {
+             method_name,
+             message_name,
+             result_type,
+             ..
+         }| {
+            quote!(
+                fn #method_name(
+                    self: &mut Self,
+                    msg: #message_name,
+                    ctx: &mut <Self as #actix::Actor>::Context,
+                ) -> #result_type;
+            )
+        },
+    );
+
+    TokenStream::from(quote!(
+        #(#messages)*
+
+        #vis trait #trait_name {
+            #(#client_fns)*
+        }
+
+        impl<A> #trait_name for #actix::Addr<A>
+        where
+            A: #actix::Actor,
+            #(#client_bounds)*
+        {
+            #(#client_impls)*
+        }
+
+        /// Handlers an actor must provide to implement the protocol, see
+        /// `#[actix_handler(implements = "...")]`
+        #vis trait #handler_trait_name: #actix::Actor {
+            #(#handler_fns)*
+        }
+    ))
+}
+
+fn parse_protocol_method(method: &syn::TraitItemMethod) -> Option<ProtocolMethod> {
+    let mut inputs = method.sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {}
+        _ => return None,
+    }
+
+    let args = inputs
+        .map(|input| match input {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => Some((pat_ident.ident.clone(), (*pat_type.ty).clone())),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let result_type = match &method.sig.output {
+        ReturnType::Default => syn::parse_quote!(()),
+        ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+    let method_name = method.sig.ident.clone();
+
+    Some(ProtocolMethod {
+        message_name: format_ident!("{}", to_camel_case(&method_name.to_string())),
+        method_name,
+        args,
+        result_type,
+    })
+}
+
+/// Implements the `...Handler` trait of each protocol named in `implements = "..."` by
+/// delegating to the handler methods assigned to it.
+pub fn expand_protocol_impl(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    // With `no_trait_decl`, `implements` names `...Addr` traits instead, see `expand_addr`
+    if options.no_trait_decl {
+        return TokenStream::new();
+    }
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+
+    let impls = options.implements.iter().map(|protocol| {
+        let handler_trait = handler_trait_path(protocol);
+        let handler_fns = impl_context
+            .handlers
+            .iter()
+            .filter(|handler_context| {
+                implemented_trait(options, handler_context)
+                    .is_some_and(|implemented| same_path(implemented, protocol))
+            })
+            .map(
+                |HandlerContext {
+                     method_name,
+                     message_type_name,
+                     result_type,
+                     ..
+                 }| {
+                    quote_spanned!(method_name.span() =>
+                        fn #method_name(
+                            &mut self,
+                            msg: #message_type_name,
+                            ctx: &mut <Self as #actix::Actor>::Context,
+                        ) -> #result_type {
+                            #type_name::#method_name(self, msg, ctx)
+                        }
+                    )
+                },
+            );
+
+        quote_spanned!(protocol.span() =>
+            impl #handler_trait for #type_name {
+                #(#handler_fns)*
+            }
+        )
+    });
+
+    TokenStream::from(quote!(#(#impls)*))
+}
+
+/// The trait a handler is implemented for: its own `#[handler(implements = "...")]`, or the only
+/// trait listed on the block.
+pub fn implemented_trait<'a>(
+    options: &'a Options,
+    handler_context: &'a HandlerContext,
+) -> Option<&'a Path> {
+    match &handler_context.options.implements {
+        Some(implements) => Some(implements),
+        None if options.implements.len() == 1 => options.implements.first(),
+        None => None,
+    }
+}
+
+/// Checks every handler of a block implementing several traits is assigned to one of them
+pub fn validate_implemented_traits(
+    options: &Options,
+    impl_context: &ImplContext,
+) -> Result<(), syn::Error> {
+    for handler_context in &impl_context.handlers {
+        match &handler_context.options.implements {
+            Some(implements)
+                if !options
+                    .implements
+                    .iter()
+                    .any(|listed| same_path(listed, implements)) =>
+            {
+                return Err(syn::Error::new(
+                    implements.span(),
+                    format!(
+                        "'{}' isn't listed in #[actix_handler(implements = \"...\")]",
+                        quote!(#implements).to_string().replace(' ', "")
+                    ),
+                ));
+            }
+            None if options.implements.len() > 1 => {
+                return Err(syn::Error::new(
+                    handler_context.method_name.span(),
+                    format!(
+                        "'{}' must pick one of the implemented traits with #[handler(implements = \"...\")]",
+                        handler_context.method_name
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// `path::Greeter` becomes `path::GreeterHandler`
+fn handler_trait_path(protocol: &Path) -> TokenStream2 {
+    let mut handler_trait = protocol.clone();
+    if let Some(segment) = handler_trait.segments.last_mut() {
+        segment.ident = format_ident!("{}Handler", segment.ident);
+    }
+    quote!(#handler_trait)
+}
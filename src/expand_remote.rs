@@ -0,0 +1,219 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::expand_addr::{addr_method_name, ImplContext};
+use crate::expand_message_enum::message_variant_name;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_ident;
+
+/// Expands the `remote` option: serializable request/response envelopes, a server adaptor
+/// forwarding framed requests to an `Addr` and a client exposing the handler methods over any
+/// `Read + Write` stream.
+///
+/// Frames are a big-endian `u32` length followed by the JSON encoded envelope, up to
+/// `max_frame_bytes` long.
+pub fn expand_remote(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    if !options.remote {
+        return TokenStream::new();
+    }
+
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+    let prefix = type_ident(type_name);
+    let request_name = format_ident!("{}RemoteRequest", prefix);
+    let response_name = format_ident!("{}RemoteResponse", prefix);
+    let error_name = format_ident!("{}RemoteError", prefix);
+    let server_name = format_ident!("{}RemoteServer", prefix);
+    let client_name = format_ident!("{}RemoteClient", prefix);
+
+    // Internal messages hidden with `no_addr` can't be called from outside either
+    let variants: Vec<(Ident, &HandlerContext)> = impl_context
+        .handlers
+        .iter()
+        .filter(|handler_context| !handler_context.options.no_addr)
+        .map(|handler_context| (message_variant_name(handler_context), handler_context))
+        .collect();
+
+    let request_variants = variants.iter().map(|(variant, handler_context)| {
+        let message_type_name = &handler_context.message_type_name;
+        quote!(#variant(#message_type_name))
+    });
+    let response_variants = variants.iter().map(|(variant, handler_context)| {
+        let message_type_name = &handler_context.message_type_name;
+        quote!(#variant(<#message_type_name as #actix::Message>::Result))
+    });
+    let server_arms = variants.iter().map(|(variant, _)| {
+        quote!(
+            #request_name::#variant(msg) => match ::futures::executor::block_on(self.addr.send(msg)) {
+                ::std::result::Result::Ok(result) => #response_name::#variant(result),
+                ::std::result::Result::Err(err) => #response_name::MailboxError(err.to_string()),
+            },
+        )
+    });
+    let client_methods = variants.iter().map(|(variant, handler_context)| {
+        let message_type_name = &handler_context.message_type_name;
+        let method_name = addr_method_name(options, handler_context);
+        quote!(
+            pub fn #method_name(
+                self: &Self,
+                msg: #message_type_name
+            ) -> ::std::result::Result<<#message_type_name as #actix::Message>::Result, #error_name> {
+                match self.call(#request_name::#variant(msg))? {
+                    #response_name::#variant(result) => ::std::result::Result::Ok(result),
+                    #response_name::MailboxError(err) => ::std::result::Result::Err(#error_name::Mailbox(err)),
+                    _ => ::std::result::Result::Err(#error_name::UnexpectedResponse),
+                }
+            }
+        )
+    });
+
+    let max_frame_bytes = options.max_frame_bytes.unwrap_or(DEFAULT_MAX_FRAME_BYTES);
+    let framing = expand_framing(&error_name, max_frame_bytes);
+
+    let output: TokenStream2 = quote!(
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        enum #request_name {
+            #(#request_variants,)*
+        }
+
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        enum #response_name {
+            #(#response_variants,)*
+            MailboxError(::std::string::String),
+        }
+
+        impl #request_name {
+            #framing
+        }
+
+        impl #response_name {
+            #framing
+        }
+
+        #[derive(Debug)]
+        enum #error_name {
+            Io(::std::io::Error),
+            Serde(::serde_json::Error),
+            Mailbox(::std::string::String),
+            UnexpectedResponse,
+            FrameTooLarge(usize),
+        }
+
+        impl ::std::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    #error_name::Io(err) => write!(f, "remote transport failed: {}", err),
+                    #error_name::Serde(err) => write!(f, "remote envelope is invalid: {}", err),
+                    #error_name::Mailbox(err) => write!(f, "remote actor failed: {}", err),
+                    #error_name::UnexpectedResponse => write!(f, "remote response doesn't match request"),
+                    #error_name::FrameTooLarge(length) => write!(
+                        f,
+                        "remote frame of {} bytes exceeds the limit of {} bytes",
+                        length,
+                        #max_frame_bytes
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        #[allow(dead_code)]
+        struct #server_name {
+            addr: #actix::Addr<#type_name>,
+        }
+
+        #[allow(dead_code)]
+        impl #server_name {
+            pub fn new(addr: #actix::Addr<#type_name>) -> Self {
+                #server_name { addr }
+            }
+
+            /// Answers requests read from `stream` until it is closed. This blocks on each
+            /// request, so it must not run on the actor's own arbiter.
+            pub fn serve<S: ::std::io::Read + ::std::io::Write>(
+                self: &Self,
+                mut stream: S,
+            ) -> ::std::result::Result<(), #error_name> {
+                while let ::std::option::Option::Some(request) = #request_name::read_frame(&mut stream)? {
+                    let response = match request {
+                        #(#server_arms)*
+                    };
+                    response.write_frame(&mut stream)?;
+                }
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        #[allow(dead_code)]
+        struct #client_name<S> {
+            stream: ::std::sync::Mutex<S>,
+        }
+
+        #[allow(dead_code)]
+        impl<S: ::std::io::Read + ::std::io::Write> #client_name<S> {
+            pub fn new(stream: S) -> Self {
+                #client_name {
+                    stream: ::std::sync::Mutex::new(stream),
+                }
+            }
+
+            pub fn call(self: &Self, request: #request_name) -> ::std::result::Result<#response_name, #error_name> {
+                let mut stream = self.stream.lock().unwrap_or_else(|err| err.into_inner());
+                request.write_frame(&mut *stream)?;
+                #response_name::read_frame(&mut *stream)?.ok_or_else(|| {
+                    #error_name::Io(::std::io::ErrorKind::UnexpectedEof.into())
+                })
+            }
+
+            #(#client_methods)*
+        }
+    );
+
+    TokenStream::from(output)
+}
+
+/// Frames larger than this are rejected unless `max_frame_bytes` is set, so a peer can't make the
+/// server allocate up to 4 GiB per frame
+const DEFAULT_MAX_FRAME_BYTES: u32 = 16 * 1024 * 1024;
+
+fn expand_framing(error_name: &Ident, max_frame_bytes: u32) -> TokenStream2 {
+    quote!(
+        fn write_frame<W: ::std::io::Write>(self: &Self, writer: &mut W) -> ::std::result::Result<(), #error_name> {
+            let body = ::serde_json::to_vec(self).map_err(#error_name::Serde)?;
+            if body.len() > #max_frame_bytes as usize {
+                return ::std::result::Result::Err(#error_name::FrameTooLarge(body.len()));
+            }
+            writer
+                .write_all(&(body.len() as u32).to_be_bytes())
+                .and_then(|_| writer.write_all(&body))
+                .and_then(|_| writer.flush())
+                .map_err(#error_name::Io)
+        }
+
+        fn read_frame<R: ::std::io::Read>(
+            reader: &mut R,
+        ) -> ::std::result::Result<::std::option::Option<Self>, #error_name> {
+            let mut length = [0u8; 4];
+            match reader.read_exact(&mut length) {
+                ::std::result::Result::Ok(()) => {}
+                ::std::result::Result::Err(err) if err.kind() == ::std::io::ErrorKind::UnexpectedEof => {
+                    return ::std::result::Result::Ok(::std::option::Option::None);
+                }
+                ::std::result::Result::Err(err) => return ::std::result::Result::Err(#error_name::Io(err)),
+            }
+            let length = u32::from_be_bytes(length);
+            if length > #max_frame_bytes {
+                return ::std::result::Result::Err(#error_name::FrameTooLarge(length as usize));
+            }
+            let mut body = ::std::vec![0u8; length as usize];
+            reader.read_exact(&mut body).map_err(#error_name::Io)?;
+            ::serde_json::from_slice(&body)
+                .map(::std::option::Option::Some)
+                .map_err(#error_name::Serde)
+        }
+    )
+}
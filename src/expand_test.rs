@@ -0,0 +1,85 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::ItemFn;
+
+use crate::actix_version::{expand_sleep, expand_system_new};
+use crate::options::TestOptions;
+
+/// Expands an `async fn` test into a `#[test]` running it on a fresh `System`, failing it if it
+/// outlives its timeout and stopping the system once it returns.
+pub fn expand_test(options: &TestOptions, item_fn: &ItemFn) -> TokenStream {
+    let signature = &item_fn.sig;
+    if signature.asyncness.is_none() {
+        return syn::Error::new(
+            signature.fn_token.span(),
+            "#[actix_handler_macro::test] will only work on 'async fn' tests",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if !signature.inputs.is_empty() {
+        return syn::Error::new(
+            signature.inputs.span(),
+            "#[actix_handler_macro::test] functions can't take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let actix = &options.actix_path;
+    let attrs = &item_fn.attrs;
+    let vis = &item_fn.vis;
+    let name = &signature.ident;
+    let output = &signature.output;
+    let block = &item_fn.block;
+    let system_new = expand_system_new(actix);
+    let run = match options.timeout_millis {
+        Some(timeout_millis) => expand_timeout(actix, &name.to_string(), timeout_millis, block),
+        None => quote!(async move #block.await),
+    };
+
+    TokenStream::from(quote!(
+        #(#attrs)*
+        #[test]
+        #vis fn #name() #output {
+            #[allow(unused_mut)]
+            let mut system = #system_new;
+            system.block_on(async move {
+                let output = #run;
+                #actix::System::current().stop();
+                output
+            })
+        }
+    ))
+}
+
+/// Races the test body against a timer, panicking if the timer wins. actix 0.10 doesn't re-export
+/// tokio's `timeout`, so the race is polled by hand.
+fn expand_timeout(
+    actix: &syn::Path,
+    name: &str,
+    timeout_millis: u64,
+    block: &syn::Block,
+) -> TokenStream2 {
+    let timeout = quote!(::std::time::Duration::from_millis(#timeout_millis));
+    let sleep = expand_sleep(actix, timeout.clone());
+
+    quote!({
+        let mut body = ::std::boxed::Box::pin(async move #block);
+        let mut deadline = ::std::boxed::Box::pin(#sleep);
+        ::std::future::poll_fn(move |cx| {
+            if let ::std::task::Poll::Ready(output) =
+                ::std::future::Future::poll(body.as_mut(), cx)
+            {
+                return ::std::task::Poll::Ready(output);
+            }
+            if ::std::future::Future::poll(deadline.as_mut(), cx).is_ready() {
+                panic!("test `{}` timed out after {:?}", #name, #timeout);
+            }
+            ::std::task::Poll::Pending
+        })
+        .await
+    })
+}
@@ -0,0 +1,70 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Ident, ImplItem, ItemImpl, Type};
+
+use crate::options::{take_timer, Options, Timer, TimerKind};
+use crate::utils::push_error;
+
+/// Inherent method the generated `started` of `#[derive(Actor)] #[actor(timers)]` calls
+pub const START_TIMERS_METHOD: &str = "start_timers";
+
+pub struct TimerContext {
+    pub(crate) method_name: Ident,
+    pub(crate) timer: Timer,
+}
+
+/// Collects the methods marked with `#[interval(...)]` or `#[delayed(...)]`, removing the
+/// attributes so the impl block compiles as written. Every invalid attribute is reported.
+pub fn parse_timers(item_impl: &mut ItemImpl) -> syn::Result<Vec<TimerContext>> {
+    let mut timers = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for item in &mut item_impl.items {
+        if let ImplItem::Method(method) = item {
+            match take_timer(&mut method.attrs) {
+                Ok(Some(timer)) => timers.push(TimerContext {
+                    method_name: method.sig.ident.clone(),
+                    timer,
+                }),
+                Ok(None) => {}
+                Err(err) => push_error(&mut errors, err),
+            }
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(timers),
+    }
+}
+
+/// Expands a `start_timers` method scheduling every timer on the actor's context
+pub fn expand_timers(options: &Options, ty: &Type, timers: &[TimerContext]) -> TokenStream {
+    if timers.is_empty() {
+        return TokenStream::new();
+    }
+
+    let actix = &options.actix_path;
+    let start_timers = Ident::new(START_TIMERS_METHOD, proc_macro2::Span::call_site());
+    let schedules = timers.iter().map(|TimerContext { method_name, timer }| {
+        let millis = timer.millis;
+        let run = match timer.kind {
+            TimerKind::Interval => quote!(run_interval),
+            TimerKind::Delayed => quote!(run_later),
+        };
+        quote!(
+            #actix::AsyncContext::#run(
+                ctx,
+                ::std::time::Duration::from_millis(#millis),
+                |act, ctx| act.#method_name(ctx),
+            );
+        )
+    });
+
+    TokenStream::from(quote!(
+        impl #ty {
+            fn #start_timers(self: &mut Self, ctx: &mut #actix::Context<Self>) {
+                #(#schedules)*
+            }
+        }
+    ))
+}
@@ -0,0 +1,86 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Ident, Type};
+
+use crate::expand_addr::ImplContext;
+use crate::expand_method_handlers::HandlerContext;
+use crate::options::Options;
+use crate::utils::type_ident;
+
+fn envelope_name(type_name: &Type) -> Ident {
+    format_ident!("{}Envelope", type_ident(type_name))
+}
+
+/// The message type `...Addr` methods send: the message itself, or the message wrapped in the
+/// actor's `...Envelope` when `trace_context` is set
+pub fn expand_sent_type(options: &Options, type_name: &Type, message: &Type) -> TokenStream2 {
+    match &options.trace_context {
+        Some(_) => {
+            let envelope_name = envelope_name(type_name);
+            quote!(#envelope_name<#message>)
+        }
+        None => quote!(#message),
+    }
+}
+
+/// Wraps `msg` into an envelope capturing the current trace context, if `trace_context` is set
+pub fn expand_sent_message(options: &Options, type_name: &Type, msg: TokenStream2) -> TokenStream2 {
+    match &options.trace_context {
+        Some(trace_context) => {
+            let envelope_name = envelope_name(type_name);
+            quote!(#envelope_name {
+                msg: #msg,
+                context: #trace_context::capture(),
+            })
+        }
+        None => msg,
+    }
+}
+
+/// Expands the `...Envelope` message and a handler for each enveloped message, restoring the
+/// captured context while the wrapped message is handled.
+pub fn expand_trace_context(options: &Options, impl_context: &ImplContext) -> TokenStream {
+    let trace_context = match &options.trace_context {
+        Some(trace_context) => trace_context,
+        None => return TokenStream::new(),
+    };
+    let actix = &options.actix_path;
+    let type_name = &impl_context.type_name;
+    let envelope_name = envelope_name(type_name);
+
+    let handlers = impl_context.handlers.iter().map(
+        |HandlerContext {
+             message_type_name, ..
+         }| {
+            quote!(
+                impl #actix::Handler<#envelope_name<#message_type_name>> for #type_name {
+                    type Result = <Self as #actix::Handler<#message_type_name>>::Result;
+
+                    fn handle(
+                        &mut self,
+                        envelope: #envelope_name<#message_type_name>,
+                        ctx: &mut Self::Context,
+                    ) -> Self::Result {
+                        let _context = #trace_context::enter(envelope.context);
+                        <Self as #actix::Handler<#message_type_name>>::handle(self, envelope.msg, ctx)
+                    }
+                }
+            )
+        },
+    );
+
+    TokenStream::from(quote!(
+        /// A message sent through the `...Addr` trait, along with the sender's trace context
+        struct #envelope_name<M> {
+            msg: M,
+            context: #trace_context,
+        }
+
+        impl<M: #actix::Message> #actix::Message for #envelope_name<M> {
+            type Result = M::Result;
+        }
+
+        #(#handlers)*
+    ))
+}
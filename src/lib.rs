@@ -22,18 +22,40 @@
 //!     }
 //! }
 //! ```
+//! ## actix versions
+//! Generated code targets actix 0.10 by default. Enable the `actix-0-12` or `actix-0-13` feature
+//! (with `default-features = false`) to target a newer release. If several are enabled, the newest
+//! one wins.
+mod actix_version;
 mod actor_derive;
 mod expand_addr;
+mod expand_cache;
+mod expand_catch_unwind;
+mod expand_ctx_ext;
+mod expand_guard;
+mod expand_handler_info;
+mod expand_harness;
 mod expand_impl_handlers;
+mod expand_journal;
+mod expand_json_rpc;
+mod expand_message_enum;
 mod expand_method_handlers;
+mod expand_observer;
+mod expand_protocol;
+mod expand_remote;
+mod expand_test;
+mod expand_timers;
+mod expand_trace_context;
 mod options;
 mod utils;
 
 use expand_impl_handlers::expand_item_impl;
-use options::{parse_options, Options};
+use expand_protocol::expand_protocol;
+use expand_test::expand_test;
+use options::{parse_options, parse_test_options, strip_method_attrs, Options};
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, AttributeArgs, DeriveInput, Item};
+use syn::{parse_macro_input, AttributeArgs, DeriveInput, Item, ItemFn, ItemTrait};
 use utils::compilation_error;
 
 /// Allows writing Actix actors with impl blocks.
@@ -175,13 +197,623 @@ use utils::compilation_error;
 ///     }
 /// }
 /// ```
+///
+/// ## Observing handlers
+///
+/// `#[actix_handler(observer = "path::to::Observer")]` calls the observer's associated functions
+/// around every generated `handle`, with the actor, method and message type names:
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use actix::Message;
+/// use actix_handler_macro::{actix_handler, Actor};
+///
+/// struct LogObserver;
+///
+/// impl LogObserver {
+///     fn before_handle(actor: &'static str, method: &'static str, message: &'static str) {
+///         println!("{}::{} received {}", actor, method, message);
+///     }
+///
+///     fn after_handle(actor: &'static str, method: &'static str, message: &'static str, elapsed: Duration) {
+///         println!("{}::{} handled {} in {:?}", actor, method, message, elapsed);
+///     }
+/// }
+///
+/// #[derive(Actor)]
+/// struct Example;
+/// #[derive(Message)]
+/// #[rtype(result = "String")]
+/// struct Greeting { name: String }
+///
+/// #[actix_handler(observer = "LogObserver")]
+/// impl Example {
+///     fn greet(&self, message: Greeting, _ctx: &actix::Context<Self>) -> String {
+///         format!("Hello {}", message.name)
+///     }
+/// }
+/// ```
+///
+/// With the `metrics` feature enabled, `#[actix_handler(metrics)]` records an
+/// `actix_handler_calls_total` counter and an `actix_handler_duration_seconds` histogram through the
+/// [`metrics`](https://docs.rs/metrics) crate, labelled by `actor`, `method` and `message`. The
+/// calling crate must depend on `metrics` itself.
+///
+/// The elapsed time covers the synchronous part of the handler only; futures returned in the
+/// handler result are not awaited.
+///
+/// Mailbox depth isn't reported: actix doesn't expose how many messages are queued for an actor.
+///
+/// ## Panic isolation
+///
+/// `#[handler(catch_unwind)]` runs the method inside `std::panic::catch_unwind`. On panic the actor
+/// is stopped through its context and the pending request is answered with `()`.
+/// `#[handler(catch_unwind = "path::to::fn")]` instead converts the panic payload into the handler's
+/// result and keeps the actor running. `#[actix_handler(catch_unwind)]` applies the first form to
+/// every handler of the block without one of its own. Only handlers returning `()` can be stopped
+/// this way; any other result would be made up, so those handlers must set a converter.
+///
+/// Panics are reported to `#[actix_handler(panic_hook = "path::to::fn")]` when set:
+///
+/// ```rust
+/// use std::any::Any;
+///
+/// use actix::Message;
+/// use actix_handler_macro::{actix_handler, Actor};
+///
+/// fn report_panic(actor: &'static str, method: &'static str, _payload: &(dyn Any + Send)) {
+///     eprintln!("{}::{} panicked", actor, method);
+/// }
+///
+/// fn panic_to_error(_payload: Box<dyn Any + Send>) -> Result<u32, String> {
+///     Err("division failed".to_string())
+/// }
+///
+/// #[derive(Actor)]
+/// struct Example;
+/// #[derive(Message)]
+/// #[rtype(result = "Result<u32, String>")]
+/// struct Divide(u32, u32);
+///
+/// #[actix_handler(panic_hook = "report_panic")]
+/// impl Example {
+///     #[handler(catch_unwind = "panic_to_error")]
+///     fn divide(&self, message: Divide, _ctx: &actix::Context<Self>) -> Result<u32, String> {
+///         Ok(message.0 / message.1)
+///     }
+/// }
+/// ```
+///
+/// ## Message enum
+///
+/// `#[actix_handler(message_enum = "ExampleMsg")]` also expands a single message type representing
+/// every message the actor accepts, which is convenient for logging, persistence or transport:
+///
+/// ```ignore
+/// // Example output
+/// enum ExampleMsg {
+///     Greet(Greeting),
+/// }
+/// impl From<Greeting> for ExampleMsg { /* ... */ }
+///
+/// enum ExampleMsgResult {
+///     Greet(String),
+/// }
+/// impl actix::Handler<ExampleMsg> for Example { /* dispatches to `greet` */ }
+/// ```
+///
+/// Variants are named after the handler methods. Each result variant holds the method's return type,
+/// so handlers returning futures can't be dispatched through the enum.
+///
+/// ## Remote actors
+///
+/// With the `remote` feature enabled, `#[actix_handler(remote)]` exposes the handlers to other
+/// processes over any `Read + Write` stream, such as a `TcpStream` or a `UnixStream`:
+///
+/// * `ExampleRemoteRequest` / `ExampleRemoteResponse` are serializable envelopes with one variant
+///   per handler
+/// * `ExampleRemoteServer::new(addr).serve(stream)` answers requests by forwarding them to the `Addr`
+/// * `ExampleRemoteClient::new(stream)` has a blocking method per handler, e.g. `client.greet(msg)`.
+///   It mirrors the `ExampleAddr` method names but doesn't implement that trait, whose methods return
+///   actix request futures
+///
+/// Messages and their results must implement `serde::Serialize` and `serde::Deserialize`. Frames
+/// are a big-endian `u32` length followed by JSON. Frames over 16 MiB, or over
+/// `#[actix_handler(remote, max_frame_bytes = ...)]`, are refused on both ends with
+/// `ExampleRemoteError::FrameTooLarge`. The calling crate must depend on `serde` (with
+/// the `derive` feature), `serde_json` and `futures`. The server blocks on every request, so it must
+/// run on its own thread rather than on the actor's arbiter.
+///
+/// ## JSON-RPC
+///
+/// `#[actix_handler(json_rpc)]` expands an `ExampleJsonRpc` trait for `Addr<Example>` with a
+/// `dispatch_json(method, params)` method. It looks up the handler by method name, deserializes
+/// `params` into its message, sends it and serializes the result:
+///
+/// ```ignore
+/// let result: serde_json::Value = addr.dispatch_json("greet", json!({ "name": "you" })).await?;
+/// ```
+///
+/// Failures are reported as `ExampleJsonRpcError`, whose `code()` is the matching JSON-RPC 2.0
+/// error code. Messages must implement `serde::Deserialize` and results `serde::Serialize`, and the
+/// calling crate must depend on `serde_json`.
+///
+/// ## actix crate path
+///
+/// Generated code refers to actix as `::actix`. If actix is re-exported under another path, set it
+/// with `#[actix_handler(crate = "path::to::actix")]` and `#[actor(crate = "path::to::actix")]`
+/// (the latter is also read by `#[derive(Supervised)]`, `#[derive(ArbiterService)]` and
+/// `#[derive(SystemService)]`). A path that doesn't parse is a compile error pointing at it.
+///
+/// ## Renaming `...Addr` methods
+///
+/// Trait methods are named after their handlers. `#[actix_handler(addr_prefix = "send_",
+/// addr_suffix = "_request")]` decorates every name, `#[handler(addr_name = "...")]` renames a
+/// single method and `#[handler(no_addr)]` leaves an internal message out of the trait entirely:
+///
+/// ```ignore
+/// #[actix_handler(addr_prefix = "send_")]
+/// impl Example {
+///     // `ExampleAddr::send_greet`
+///     fn greet(&self, message: Greeting, _ctx: &ExampleContext) -> String { /* ... */ }
+///
+///     // `ExampleAddr::count`
+///     #[handler(addr_name = "count")]
+///     fn handle_count(&self, message: Count, _ctx: &ExampleContext) -> usize { /* ... */ }
+///
+///     // Only reachable through `Addr::send`
+///     #[handler(no_addr)]
+///     fn flush(&self, message: Flush, _ctx: &ExampleContext) { /* ... */ }
+/// }
+/// ```
+///
+/// The same names are used by the `json_rpc` dispatcher and the `remote` client, and `no_addr`
+/// handlers can't be reached through either of them.
+///
+/// ## Sending batches
+///
+/// Every `...Addr` trait method also gets a provided `<method>_many` variant, which sends each
+/// message of an iterator and resolves to the replies in the same order:
+///
+/// ```ignore
+/// let replies: Vec<Result<String, MailboxError>> = addr.greet_many(greetings).await;
+/// ```
+///
+/// `#[actix_handler(max_in_flight = 16)]` bounds how many replies are awaited at once; the next
+/// message isn't taken from the iterator until one of them resolves.
+///
+/// The `_many` methods require `Self: Sized`, so they aren't available on a `dyn ...Addr` and don't
+/// stop the trait from being used as one. They return a boxed future to stay within stable Rust
+/// before 1.75.
+///
+/// ## Context helpers
+///
+/// With `#[actix_handler(ctx_ext)]`, a `...CtxExt` trait is also implemented for the actor's
+/// `Context`, with typed helpers for messages the actor sends to itself:
+///
+/// ```ignore
+/// // Example output
+/// trait ExampleCtxExt {
+///     fn notify_greet(&mut self, msg: Greeting);
+///     fn notify_greet_later(&mut self, msg: Greeting, after: Duration) -> SpawnHandle;
+///     fn run_greet_interval<F>(&mut self, interval: Duration, f: F) -> SpawnHandle
+///     where
+///         F: FnMut(&mut Example) -> Greeting + 'static;
+/// }
+/// ```
+///
+/// It's named after `trait_name` when one is set (`trait_name = "ExampleAdmin"` gives
+/// `ExampleAdminCtxExt`), so each `#[actix_handler]` block of an actor can have its own.
+///
+/// ## Timers
+///
+/// Methods taking only the context can be scheduled with `#[interval(secs = 5)]` or
+/// `#[delayed(millis = 500)]`. They're collected into a generated `start_timers` method, which
+/// `#[derive(Actor)]` calls from `started` when the actor is marked `#[actor(timers)]`:
+///
+/// ```ignore
+/// #[derive(Actor)]
+/// #[actor(timers)]
+/// struct Example;
+///
+/// #[actix_handler]
+/// impl Example {
+///     #[interval(secs = 5)]
+///     fn tick(&mut self, ctx: &mut Context<Self>) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Actors implementing `Actor` by hand can call `self.start_timers(ctx)` from their own `started`.
+///
+/// `secs` and `millis` add up. An `#[interval]` needs a nonzero duration. A missing or non-integer
+/// duration, or any other key, is a compile error.
+///
+/// ## Restart policies
+///
+/// `#[derive(Supervised)]` takes an optional `#[supervised(...)]` restart policy. Restarts are
+/// tracked in a generated `...RestartState`, which the actor keeps in a field marked
+/// `#[supervised(state)]`:
+///
+/// ```ignore
+/// #[derive(Actor, Supervised, Default)]
+/// #[supervised(max_restarts = 5, within_secs = 60, backoff = "exponential", on_exhausted = "give_up")]
+/// struct Example {
+///     #[supervised(state)]
+///     restarts: ExampleRestartState,
+/// }
+///
+/// fn give_up(actor: &mut Example, ctx: &mut Context<Example>) {
+///     // ...
+/// }
+/// ```
+///
+/// * `max_restarts` - restarts allowed within the window, unlimited by default
+/// * `within_secs` - only count restarts in the last `within_secs` seconds
+/// * `backoff` - `"constant"` or `"exponential"`; the restarted actor waits before handling
+///   messages again. The delay starts at `backoff_millis`, 100ms by default
+/// * `on_exhausted` - called as `on_exhausted(actor, ctx)` once the budget runs out
+///
+/// Unknown options and invalid values, such as a misspelled `backoff`, are compile errors.
+///
+/// With `#[derive(Actor)]`, an actor that stops itself is restarted in place from the generated
+/// `stopping`: it keeps its state, context and spawned futures, and `started` isn't run again. Start
+/// it with `start()`. Once the budget is exhausted it stops for good and its mailbox is closed, so
+/// later requests fail with `MailboxError::Closed`. Under actix's `Supervisor`, which restarts an
+/// actor for as long as an address to it is alive, an exhausted actor is parked instead: it no longer
+/// handles messages, but requests to it aren't failed either.
+///
+/// ## Starting actors
+///
+/// `#[derive(Actor)]` can generate constructors for where the actor should run:
+///
+/// ```ignore
+/// // Example::start_sync_pool() starts 4 default-constructed actors on a SyncArbiter
+/// #[derive(Actor, Default)]
+/// #[actor(context = "SyncContext", threads = 4)]
+/// struct Example;
+///
+/// // Placed.start_on_new_arbiter() runs the actor on an arbiter of its own
+/// #[derive(Actor)]
+/// #[actor(arbiter = "new")]
+/// struct Placed;
+/// ```
+///
+/// Both return the actor's `Addr<Self>`. `context = "SyncContext"` and `context = "Context"` refer to
+/// actix's own context types. `threads` without a `SyncContext` is a compile error, since only those
+/// actors can run on a `SyncArbiter`.
+///
+/// ## Registry accessors
+///
+/// `#[derive(ArbiterService)]` and `#[derive(SystemService)]` also generate a function fetching
+/// the actor from the arbiter or system registry, named after the actor:
+///
+/// ```ignore
+/// #[derive(Actor, Supervised, ArbiterService, Default)]
+/// struct Adder;
+///
+/// // Same as Adder::from_registry().add(Add(3))
+/// adder_addr().add(Add(3)).await?;
+/// ```
+///
+/// ## Publishing events
+///
+/// Actors listing events in `#[publishes(...)]` keep a generated `...Subscribers` list in a field
+/// marked `#[publishes(subscribers)]`. Other actors subscribe with the generated
+/// `...Subscribe<Event>` and `...Unsubscribe<Event>` messages, and the actor broadcasts with a
+/// `publish_*` method per event:
+///
+/// ```ignore
+/// #[derive(Actor, Default)]
+/// #[publishes(PriceChanged)]
+/// struct Ticker {
+///     #[publishes(subscribers)]
+///     subscribers: TickerSubscribers,
+/// }
+///
+/// // ticker.send(TickerSubscribe(watcher.recipient())).await?;
+/// // Then, from a Ticker handler:
+/// self.publish_price_changed(ctx, PriceChanged(42));
+/// ```
+///
+/// Events must be `Clone`. Subscribers that have stopped are dropped on the next publish; a
+/// subscriber with a full mailbox misses that event.
+///
+/// ## Handler metadata
+///
+/// `#[actix_handler(handler_info = "path::to::HandlerInfo")]` emits a `HANDLERS` associated const
+/// listing the handled messages. Since this crate can't export runtime types, the struct is yours
+/// and only needs these fields:
+///
+/// ```ignore
+/// pub struct HandlerInfo {
+///     pub method: &'static str,
+///     pub message: &'static str,
+///     pub result: &'static str,
+///     pub message_type_id: fn() -> TypeId,
+///     pub result_type_id: fn() -> TypeId,
+/// }
+///
+/// for handler in Example::HANDLERS {
+///     println!("{}: {} -> {}", handler.method, handler.message, handler.result);
+/// }
+/// ```
+///
+/// ## Protocols
+///
+/// `#[actix_protocol]` declares a contract before any actor implements it. Each method becomes a
+/// message struct, named after the method, and the trait becomes a client trait implemented for
+/// every `Addr<A>` whose actor handles those messages:
+///
+/// ```ignore
+/// #[actix_protocol]
+/// pub trait Greeter {
+///     fn greet(&self, name: String) -> String;
+/// }
+///
+/// // Generated
+/// pub struct Greet {
+///     pub name: String,
+/// }
+/// impl Message for Greet { type Result = String; }
+///
+/// pub trait Greeter {
+///     fn greet(&self, name: String) -> Pin<Box<dyn Future<Output = Result<String, MailboxError>>>>;
+/// }
+/// impl<A> Greeter for Addr<A> where A: Handler<Greet> { /* ... */ }
+///
+/// pub trait GreeterHandler: Actor {
+///     fn greet(&mut self, msg: Greet, ctx: &mut Self::Context) -> String;
+/// }
+/// ```
+///
+/// Actors implement the `...Handler` trait with `#[actix_handler(implements = "Greeter")]`, which
+/// must provide a handler for every method. Such blocks don't generate an `...Addr` trait, since
+/// the protocol's client trait already covers them:
+///
+/// ```ignore
+/// #[actix_handler(implements = "Greeter")]
+/// impl Example {
+///     fn greet(&self, msg: Greet, _ctx: &Context<Self>) -> String {
+///         format!("Hello {}", msg.name)
+///     }
+/// }
+/// ```
+///
+/// ## Implementing traits declared elsewhere
+///
+/// `implements` takes a comma separated list of traits. When a block implements several, each
+/// handler picks one with `#[handler(implements = "...")]`:
+///
+/// ```ignore
+/// #[actix_handler(implements = "Greeter, Counter")]
+/// impl Example {
+///     #[handler(implements = "Greeter")]
+///     fn greet(&self, msg: Greet, _ctx: &Context<Self>) -> String { /* ... */ }
+///
+///     #[handler(implements = "Counter")]
+///     fn increment(&mut self, msg: Increment, _ctx: &mut Context<Self>) -> usize { /* ... */ }
+/// }
+/// ```
+///
+/// Combined with `no_trait_decl`, `implements` names `...Addr` traits declared by other
+/// `#[actix_handler]` blocks (usually with `use_recipient`, so they don't mention the actor type),
+/// and the block implements them for its `Addr`. Either way, a trait method the block doesn't handle
+/// is reported on the `implements` literal, and a handler that isn't part of its trait is reported on
+/// the handler.
+///
+/// ## Trace context
+///
+/// `#[actix_handler(trace_context = "path::to::Context")]` carries the sender's context, such as a
+/// correlation id or tracing span, along with messages sent through the `...Addr` trait. The
+/// generated methods wrap each message in an `...Envelope` holding `Context::capture()`, and the
+/// envelope's handler keeps `Context::enter(context)` alive while the handler runs:
+///
+/// ```ignore
+/// struct CorrelationId(Option<u64>);
+///
+/// impl CorrelationId {
+///     // Called by the sender
+///     fn capture() -> Self { /* read the current id */ }
+///
+///     // Called around the handler, the returned guard is dropped once it returns
+///     fn enter(self) -> CorrelationGuard { /* set the current id */ }
+/// }
+/// ```
+///
+/// Handler signatures don't change, and messages sent with `Addr::send` are still handled without a
+/// context. Futures returned by handlers run after the guard is dropped.
+///
+/// ## Guards
+///
+/// `#[handler(guard = "path::to::guard")]` runs a check before the handler. The guard is called as
+/// `guard(&actor, &msg)` and returns a `Result<(), E>`; on error the handler isn't called and the
+/// reply is `Err(E.into())`, so the handler must return a `Result` whose error implements
+/// `From<E>`:
+///
+/// ```ignore
+/// fn admin_only(accounts: &Accounts, msg: &DeleteAccount) -> Result<(), Forbidden> {
+///     // ...
+/// }
+///
+/// #[actix_handler(guard = "signed_in")]
+/// impl Accounts {
+///     #[handler(guard = "admin_only")]
+///     fn delete_account(&mut self, msg: DeleteAccount, _ctx: &mut Context<Self>) -> Result<(), AccountError> {
+///         // ...
+///     }
+///
+///     #[handler(no_guard)]
+///     fn is_open(&self, msg: IsOpen, _ctx: &Context<Self>) -> Result<bool, AccountError> {
+///         Ok(true)
+///     }
+/// }
+/// ```
+///
+/// A guard set on the impl block applies to every handler without its own `guard`, unless it's
+/// marked `#[handler(no_guard)]`.
+///
+/// A guard that isn't a valid path is a compile error pointing at it, as is any option
+/// `#[actix_handler(...)]` or `#[handler(...)]` doesn't know, so a misspelled `gaurd` can't silently
+/// leave a handler unguarded.
+///
+/// ## Caching replies
+///
+/// `#[handler(cache(ttl_secs = 30, key = "field"))]` memoizes a query handler's replies, keyed on
+/// the given message field (or the whole message without `key`), which must be
+/// `Clone + Hash + Eq + 'static`. Replies must be `Clone`, and are kept forever without `ttl_secs`.
+/// Handlers marked `#[invalidates(method, ...)]` clear the named caches after they run:
+///
+/// ```ignore
+/// #[derive(Actor, Default)]
+/// struct Config {
+///     values: HashMap<String, String>,
+///     cache: ConfigHandlerCache,
+/// }
+///
+/// #[actix_handler]
+/// impl Config {
+///     #[handler(cache(ttl_secs = 30, key = "key"))]
+///     fn get_value(&mut self, msg: GetValue, _ctx: &mut Context<Self>) -> Option<String> {
+///         self.values.get(&msg.key).cloned()
+///     }
+///
+///     #[invalidates(get_value)]
+///     fn set_value(&mut self, msg: SetValue, _ctx: &mut Context<Self>) {
+///         self.values.insert(msg.0, msg.1);
+///     }
+/// }
+/// ```
+///
+/// The generated `...HandlerCache` lives in the actor's `cache` field; use
+/// `#[actix_handler(cache_field = "...")]` to pick another name. Guards run before the cache is
+/// checked.
+///
+/// With `ttl_secs`, expired replies are dropped as new ones are stored, so keys that aren't asked for
+/// again don't accumulate. Without it, every distinct key is kept until the cache is invalidated.
+/// Replies are timed with actix's clock, so `actix_handler_sim::advance` expires them in tests.
+///
+/// ## Test harness
+///
+/// `#[actix_handler(harness)]` generates an `...Harness` owning the actor and a detached context,
+/// with a method per handler calling it synchronously. Tests can exercise handler logic and inspect
+/// the actor's state without starting a `System`:
+///
+/// ```ignore
+/// let mut harness = ExampleHarness::new(Example::default());
+/// assert_eq!(harness.greet(Greeting { name: "Alice".into() }), "Hello Alice");
+/// assert_eq!(harness.actor.greeted, 1);
+/// ```
+///
+/// Calls go through the generated `Handler` impls, so guards, caching and `catch_unwind` apply.
+/// Messages the handler sends to itself through the context are never delivered.
+///
+/// ## Testing with virtual time
+///
+/// actix timers run on tokio's clock, so tests of intervals and timeouts don't need to wait on the
+/// wall clock. The companion `actix-handler-sim` crate pauses that clock from inside the `System`
+/// and moves virtual time forward. A paused clock jumps straight to the next timer once every task is
+/// idle, so timers fire in deadline order and each one's work runs before the next. Its `Trace`
+/// observer records the messages handled, in delivery order:
+///
+/// ```toml
+/// [dev-dependencies]
+/// actix-handler-sim = { version = "0.1", default-features = false, features = ["actix-0-13"] }
+/// ```
+///
+/// ```ignore
+/// #[actix_handler(observer = "actix_handler_sim::Trace")]
+/// impl Janitor {
+///     #[interval(secs = 60)]
+///     fn tick(&mut self, ctx: &mut Context<Self>) {
+///         ctx.notify(Sweep);
+///     }
+///     // ...
+/// }
+///
+/// System::new().block_on(async {
+///     actix_handler_sim::pause();
+///     let addr = Janitor::default().start();
+///
+///     actix_handler_sim::advance(Duration::from_secs(190)).await; // returns instantly
+///     assert_eq!(addr.count_sweeps(CountSweeps).await.unwrap(), 3);
+/// });
+/// assert_eq!(actix_handler_sim::Trace::take()[..3], ["Janitor::sweep"; 3]);
+/// ```
+///
+/// Its `actix-0-*` features match this crate's, and pick the tokio release whose clock is paused.
+///
+/// ## Async tests
+///
+/// `#[actix_handler_macro::test]` turns an `async fn` into a `#[test]` running on a fresh `System`,
+/// which is stopped once the test returns. `timeout_secs` or `timeout_millis` fail the test instead
+/// of letting it hang:
+///
+/// ```ignore
+/// #[actix_handler_macro::test(timeout_secs = 5)]
+/// async fn test_greet() {
+///     let addr = Example::default().start();
+///     assert_eq!(addr.greet(Greeting { name: "Alice".into() }).await.unwrap(), "Hello Alice");
+/// }
+/// ```
+///
+/// Other attributes such as `#[should_panic]` or `#[ignore]` are kept, and tests may return a
+/// `Result`. Like `#[actix_handler]`, it takes `crate = "path::to::actix"` if actix is re-exported.
+/// Unknown options and invalid values, such as `timeout = 1` or `timeout_secs = "1"`, are compile
+/// errors.
+///
+/// ## Journaling messages
+///
+/// `#[actix_handler(journal)]` records the messages of handlers marked `#[handler(persist)]` before
+/// they run, so the actor's state can be rebuilt later. It expands:
+///
+/// * `ExampleJournal`, a trait appending serialized entries and listing them back
+/// * `ExampleMemoryJournal`, whose clones share the same entries, and `ExampleFileJournal::open(path)`,
+///   appending one entry per line
+/// * `Example::replay(journal)`, which builds the actor with `Default`, re-runs the persisted methods
+///   over every entry and then keeps appending to `journal`
+///
+/// The actor holds its journal in a `journal: Option<Box<dyn ExampleJournal>>` field, or the field
+/// named by `journal_field`. Handlers only record while it is set:
+///
+/// ```ignore
+/// #[derive(Actor, Default)]
+/// struct Ledger {
+///     balance: i64,
+///     journal: Option<Box<dyn LedgerJournal>>,
+/// }
+///
+/// #[actix_handler(journal)]
+/// impl Ledger {
+///     #[handler(persist)]
+///     fn deposit(&mut self, msg: Deposit, _ctx: &mut Context<Self>) -> i64 { /* ... */ }
+/// }
+///
+/// let ledger = Ledger::replay(LedgerFileJournal::open("ledger.jsonl")?)?.start();
+/// ```
+///
+/// Entries are `[method, message]` JSON arrays, so persisted messages must implement
+/// `serde::Serialize` and `serde::Deserialize`, and the calling crate must depend on `serde_json`.
+/// Guards run before a message is recorded. Replaying calls the methods directly, with a detached
+/// context, so their results (and any futures they return) are dropped.
+///
+/// A message that can't be serialized or appended is still handled, so its request is answered, but
+/// the actor is then stopped so nothing else is applied without being recorded. The error is passed
+/// to `#[actix_handler(journal_error = "path::to::fn")]` when set, called as
+/// `journal_error(actor: &'static str, method: &'static str, error: &std::io::Error)`.
 #[proc_macro_attribute]
 pub fn actix_handler(args: TokenStream, input: TokenStream) -> TokenStream {
     let parsed_args = parse_macro_input!(args as AttributeArgs);
-    let options = parse_options(parsed_args);
-
     let mut parsed_input = parse_macro_input!(input as Item);
-    let expanded = expand_actix_handler(options, &mut parsed_input);
+    let expanded = match parse_options(parsed_args) {
+        Ok(options) => expand_actix_handler(options, &mut parsed_input),
+        Err(err) => {
+            strip_method_attrs(&mut parsed_input);
+            TokenStream::from(err.to_compile_error())
+        }
+    };
 
     let mut output = TokenStream::from(quote!(#parsed_input));
     output.extend(expanded);
@@ -195,20 +827,53 @@ fn expand_actix_handler(options: Options, input: &mut Item) -> TokenStream {
     }
 }
 
-#[proc_macro_derive(Actor, attributes(actor))]
+/// Declares a protocol as a trait, before any actor implements it. See the `actix_handler`
+/// documentation.
+#[proc_macro_attribute]
+pub fn actix_protocol(args: TokenStream, input: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as AttributeArgs);
+    let options = match parse_options(parsed_args) {
+        Ok(options) => options,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
+    let item_trait = parse_macro_input!(input as ItemTrait);
+    expand_protocol(&options, &item_trait)
+}
+
+/// Runs an `async fn` test on a fresh `System`. See the `actix_handler` documentation.
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, input: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as AttributeArgs);
+    let options = match parse_test_options(parsed_args) {
+        Ok(options) => options,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
+    let item_fn = parse_macro_input!(input as ItemFn);
+    expand_test(&options, &item_fn)
+}
+
+#[proc_macro_derive(Actor, attributes(actor, publishes))]
 pub fn actor_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     actor_derive::actor::expand(&ast).into()
 }
 
-#[proc_macro_derive(Supervised)]
+#[proc_macro_derive(Supervised, attributes(actor, supervised))]
 pub fn supervised_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     actor_derive::supervised::expand(&ast).into()
 }
 
-#[proc_macro_derive(ArbiterService)]
+#[proc_macro_derive(ArbiterService, attributes(actor))]
 pub fn arbiter_service_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     actor_derive::arbiter_service::expand(&ast).into()
 }
+
+#[proc_macro_derive(SystemService, attributes(actor))]
+pub fn system_service_derive(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(input).unwrap();
+    actor_derive::system_service::expand(&ast).into()
+}
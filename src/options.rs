@@ -1,19 +1,104 @@
 use quote::ToTokens;
-use syn::{AttributeArgs, Lit, Meta, NestedMeta};
+use syn::{
+    parse_quote, Attribute, AttributeArgs, Ident, ImplItem, Item, ItemImpl, Lit, LitInt, LitStr,
+    Meta, NestedMeta, Path,
+};
+
+pub const ACTIX_HANDLER_ATTR: &str = "actix_handler";
+pub const HANDLER_ATTR: &str = "handler";
+pub const INVALIDATES_ATTR: &str = "invalidates";
+pub const INTERVAL_ATTR: &str = "interval";
+pub const DELAYED_ATTR: &str = "delayed";
+pub const TEST_ATTR: &str = "test";
 
 pub struct Options {
     pub(crate) trait_name: Option<String>,
     pub(crate) no_trait_decl: bool,
     pub(crate) no_trait_impl: bool,
     pub(crate) use_recipient: bool,
+    pub(crate) observer: Option<Path>,
+    pub(crate) metrics: bool,
+    pub(crate) catch_unwind: bool,
+    pub(crate) panic_hook: Option<Path>,
+    pub(crate) message_enum: Option<String>,
+    pub(crate) remote: bool,
+    pub(crate) json_rpc: bool,
+    pub(crate) actix_path: Path,
+    pub(crate) addr_prefix: Option<String>,
+    pub(crate) addr_suffix: Option<String>,
+    pub(crate) max_in_flight: Option<usize>,
+    pub(crate) max_frame_bytes: Option<u32>,
+    pub(crate) handler_info: Option<Path>,
+    pub(crate) implements: Vec<Path>,
+    pub(crate) trace_context: Option<Path>,
+    pub(crate) guard: Option<Path>,
+    pub(crate) cache_field: Option<String>,
+    pub(crate) harness: bool,
+    pub(crate) journal: bool,
+    pub(crate) journal_field: Option<String>,
+    pub(crate) journal_error: Option<Path>,
+    pub(crate) ctx_ext: bool,
+}
+
+/// Options set on a single method with `#[handler(...)]`
+#[derive(Clone, Default)]
+pub struct HandlerOptions {
+    pub(crate) catch_unwind: Option<CatchUnwind>,
+    pub(crate) addr_name: Option<String>,
+    pub(crate) no_addr: bool,
+    pub(crate) implements: Option<Path>,
+    pub(crate) guard: Option<Path>,
+    pub(crate) no_guard: bool,
+    pub(crate) cache: Option<Cache>,
+    pub(crate) invalidates: Vec<Ident>,
+    pub(crate) persist: bool,
+}
+
+/// `#[handler(cache(ttl_secs = 30, key = "field"))]`
+#[derive(Clone, Default)]
+pub struct Cache {
+    /// How long replies are kept, forever (until invalidated) if unset
+    pub(crate) ttl_secs: Option<u64>,
+    /// The message field replies are keyed on, the whole message if unset
+    pub(crate) key: Option<Ident>,
+}
+
+#[derive(Clone)]
+pub enum CatchUnwind {
+    /// Stop the actor and reply with `Default::default()`
+    Stop,
+    /// Convert the panic payload into the handler result with the given function
+    Convert(Path),
 }
 
-pub fn parse_options(args: AttributeArgs) -> Options {
+pub fn parse_options(args: AttributeArgs) -> syn::Result<Options> {
     let mut options = Options {
         trait_name: None,
         use_recipient: false,
         no_trait_decl: false,
         no_trait_impl: false,
+        observer: None,
+        metrics: false,
+        catch_unwind: false,
+        panic_hook: None,
+        message_enum: None,
+        remote: false,
+        json_rpc: false,
+        actix_path: parse_quote!(::actix),
+        addr_prefix: None,
+        addr_suffix: None,
+        max_in_flight: None,
+        max_frame_bytes: None,
+        handler_info: None,
+        implements: vec![],
+        trace_context: None,
+        guard: None,
+        cache_field: None,
+        harness: false,
+        journal: false,
+        journal_field: None,
+        journal_error: None,
+        ctx_ext: false,
     };
 
     for arg in args {
@@ -29,20 +114,424 @@ pub fn parse_options(args: AttributeArgs) -> Options {
                     "no_trait_impl" => {
                         options.no_trait_impl = true;
                     }
-                    _ => {}
+                    "metrics" => {
+                        options.metrics = true;
+                    }
+                    "catch_unwind" => {
+                        options.catch_unwind = true;
+                    }
+                    "remote" => {
+                        options.remote = true;
+                    }
+                    "json_rpc" => {
+                        options.json_rpc = true;
+                    }
+                    "harness" => {
+                        options.harness = true;
+                    }
+                    "journal" => {
+                        options.journal = true;
+                    }
+                    "ctx_ext" => {
+                        options.ctx_ext = true;
+                    }
+                    _ => return Err(unknown_option(ACTIX_HANDLER_ATTR, &path)),
                 },
                 Meta::NameValue(name_value) => {
-                    if let "trait_name" = name_value.path.to_token_stream().to_string().as_str() {
-                        if let Lit::Str(trait_name) = name_value.lit {
-                            options.trait_name = Some(trait_name.value());
+                    let lit = &name_value.lit;
+                    match name_value.path.to_token_stream().to_string().as_str() {
+                        "trait_name" => {
+                            options.trait_name = Some(lit_str(lit)?.value());
+                        }
+                        "observer" => {
+                            options.observer = Some(parse_path(lit_str(lit)?)?);
+                        }
+                        "addr_prefix" => {
+                            options.addr_prefix = Some(lit_str(lit)?.value());
+                        }
+                        "addr_suffix" => {
+                            options.addr_suffix = Some(lit_str(lit)?.value());
+                        }
+                        "crate" => {
+                            options.actix_path = parse_path(lit_str(lit)?)?;
+                        }
+                        "max_frame_bytes" => {
+                            options.max_frame_bytes = Some(lit_int(lit)?.base10_parse()?);
+                        }
+                        "max_in_flight" => {
+                            options.max_in_flight = Some(lit_int(lit)?.base10_parse()?);
+                        }
+                        "handler_info" => {
+                            options.handler_info = Some(parse_path(lit_str(lit)?)?);
+                        }
+                        "implements" => {
+                            options.implements = parse_path_list(lit_str(lit)?)?;
+                        }
+                        "guard" => {
+                            options.guard = Some(parse_path(lit_str(lit)?)?);
+                        }
+                        "cache_field" => {
+                            options.cache_field = Some(lit_str(lit)?.value());
+                        }
+                        "journal_field" => {
+                            options.journal_field = Some(lit_str(lit)?.value());
+                        }
+                        "journal_error" => {
+                            options.journal_error = Some(parse_path(lit_str(lit)?)?);
                         }
+                        "trace_context" => {
+                            options.trace_context = Some(parse_path(lit_str(lit)?)?);
+                        }
+                        "message_enum" => {
+                            options.message_enum = Some(lit_str(lit)?.value());
+                        }
+                        "panic_hook" => {
+                            options.panic_hook = Some(parse_path(lit_str(lit)?)?);
+                        }
+                        _ => return Err(unknown_option(ACTIX_HANDLER_ATTR, &name_value.path)),
                     }
                 }
-                Meta::List(_) => {}
+                Meta::List(list) => return Err(unknown_option(ACTIX_HANDLER_ATTR, &list.path)),
             },
-            NestedMeta::Lit(_) => {}
+            NestedMeta::Lit(lit) => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "expected an #[actix_handler] option",
+                ))
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Parses a path given as a string literal, such as `observer = "metrics::Observer"`, pointing
+/// errors at the literal
+pub fn parse_path(lit: &LitStr) -> syn::Result<Path> {
+    lit.parse().map_err(|_| {
+        syn::Error::new(
+            lit.span(),
+            format!(
+                "expected a path such as `module::Type`, found \"{}\"",
+                lit.value()
+            ),
+        )
+    })
+}
+
+/// Parses a comma separated list of paths such as `"Greeter, admin::Counter"`, keeping the
+/// literal's span so errors point at it
+fn parse_path_list(list: &LitStr) -> syn::Result<Vec<Path>> {
+    list.value()
+        .split(',')
+        .map(|path| parse_path(&LitStr::new(path.trim(), list.span())))
+        .collect()
+}
+
+/// Removes the `#[handler(...)]` and `#[invalidates(...)]` attributes from a method and parses
+/// them. The attributes are removed even if they're invalid.
+pub fn take_handler_options(attrs: &mut Vec<Attribute>) -> syn::Result<HandlerOptions> {
+    let mut options = HandlerOptions::default();
+    let (invalidates_attrs, other_attrs): (Vec<Attribute>, Vec<Attribute>) = attrs
+        .drain(..)
+        .partition(|attr| attr.path.is_ident(INVALIDATES_ATTR));
+    let (handler_attrs, other_attrs): (Vec<Attribute>, Vec<Attribute>) = other_attrs
+        .into_iter()
+        .partition(|attr| attr.path.is_ident(HANDLER_ATTR));
+    *attrs = other_attrs;
+
+    for attr in &invalidates_attrs {
+        for nested_meta in attr_list(attr)? {
+            match nested_meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                    options.invalidates.extend(path.get_ident().cloned());
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        nested_meta,
+                        "expected the name of a cached method",
+                    ))
+                }
+            }
+        }
+    }
+
+    for attr in &handler_attrs {
+        for nested_meta in attr_list(attr)? {
+            let meta = match nested_meta {
+                NestedMeta::Meta(meta) => meta,
+                NestedMeta::Lit(lit) => {
+                    return Err(syn::Error::new_spanned(lit, "expected a #[handler] option"))
+                }
+            };
+            match meta {
+                Meta::Path(path) => match path.to_token_stream().to_string().as_str() {
+                    "catch_unwind" => {
+                        options.catch_unwind = Some(CatchUnwind::Stop);
+                    }
+                    "no_addr" => {
+                        options.no_addr = true;
+                    }
+                    "no_guard" => {
+                        options.no_guard = true;
+                    }
+                    "persist" => {
+                        options.persist = true;
+                    }
+                    _ => return Err(unknown_option(HANDLER_ATTR, &path)),
+                },
+                Meta::NameValue(name_value) => {
+                    let key = name_value.path.to_token_stream().to_string();
+                    let value = match (key.as_str(), &name_value.lit) {
+                        (
+                            "catch_unwind" | "implements" | "guard" | "addr_name",
+                            Lit::Str(value),
+                        ) => value,
+                        ("catch_unwind" | "implements" | "guard" | "addr_name", lit) => {
+                            return Err(syn::Error::new_spanned(lit, "expected a string"))
+                        }
+                        _ => return Err(unknown_option(HANDLER_ATTR, &name_value.path)),
+                    };
+                    match key.as_str() {
+                        "catch_unwind" => {
+                            options.catch_unwind = Some(CatchUnwind::Convert(parse_path(value)?));
+                        }
+                        "implements" => {
+                            options.implements = Some(parse_path(value)?);
+                        }
+                        "guard" => {
+                            options.guard = Some(parse_path(value)?);
+                        }
+                        _ => {
+                            options.addr_name = Some(value.value());
+                        }
+                    }
+                }
+                Meta::List(list) if list.path.is_ident("cache") => {
+                    options.cache = Some(parse_cache(list.nested.iter())?);
+                }
+                Meta::List(list) => return Err(unknown_option(HANDLER_ATTR, &list.path)),
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// The options of a `#[handler(...)]` or `#[invalidates(...)]` attribute. A bare `#[handler]`
+/// has none.
+fn attr_list(attr: &Attribute) -> syn::Result<Vec<NestedMeta>> {
+    match attr.parse_meta()? {
+        Meta::List(list) => Ok(list.nested.into_iter().collect()),
+        Meta::Path(_) => Ok(Vec::new()),
+        meta => Err(syn::Error::new_spanned(meta, "expected a list of options")),
+    }
+}
+
+pub fn unknown_option(attr: &str, path: &Path) -> syn::Error {
+    syn::Error::new_spanned(
+        path,
+        format!(
+            "unknown #[{}] option `{}`",
+            attr,
+            path.to_token_stream().to_string().replace(' ', "")
+        ),
+    )
+}
+
+pub fn lit_str(lit: &Lit) -> syn::Result<&LitStr> {
+    match lit {
+        Lit::Str(lit) => Ok(lit),
+        lit => Err(syn::Error::new_spanned(lit, "expected a string")),
+    }
+}
+
+pub fn lit_int(lit: &Lit) -> syn::Result<&LitInt> {
+    match lit {
+        Lit::Int(lit) => Ok(lit),
+        lit => Err(syn::Error::new_spanned(lit, "expected an integer")),
+    }
+}
+
+fn parse_cache<'a>(nested: impl Iterator<Item = &'a NestedMeta>) -> syn::Result<Cache> {
+    let mut cache = Cache::default();
+    for nested_meta in nested {
+        let name_value = match nested_meta {
+            NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    nested_meta,
+                    "expected `ttl_secs = ...` or `key = \"...\"`",
+                ))
+            }
+        };
+        match (
+            name_value.path.to_token_stream().to_string().as_str(),
+            &name_value.lit,
+        ) {
+            ("ttl_secs", Lit::Int(ttl_secs)) => {
+                cache.ttl_secs = Some(ttl_secs.base10_parse()?);
+            }
+            ("key", Lit::Str(key)) => {
+                cache.key = Some(key.parse().map_err(|_| {
+                    syn::Error::new(
+                        key.span(),
+                        format!("expected a field name, found \"{}\"", key.value()),
+                    )
+                })?);
+            }
+            ("ttl_secs", lit) => return Err(syn::Error::new_spanned(lit, "expected an integer")),
+            ("key", lit) => return Err(syn::Error::new_spanned(lit, "expected a string")),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    "unknown cache option, expected `ttl_secs` or `key`",
+                ))
+            }
+        }
+    }
+    Ok(cache)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimerKind {
+    /// `#[interval(...)]`, run with `AsyncContext::run_interval`
+    Interval,
+    /// `#[delayed(...)]`, run once with `AsyncContext::run_later`
+    Delayed,
+}
+
+/// A method scheduled when the actor starts, declared with `#[interval(secs = 5)]` or
+/// `#[delayed(millis = 500)]`
+#[derive(Clone, Debug)]
+pub struct Timer {
+    pub(crate) kind: TimerKind,
+    pub(crate) millis: u64,
+}
+
+/// Removes this crate's attributes from the methods of an `impl` block that won't be expanded, so
+/// they don't add errors of their own
+pub fn strip_method_attrs(item: &mut Item) {
+    if let Item::Impl(item_impl) = item {
+        strip_impl_method_attrs(item_impl);
+    }
+}
+
+pub fn strip_impl_method_attrs(item_impl: &mut ItemImpl) {
+    for impl_item in &mut item_impl.items {
+        if let ImplItem::Method(method) = impl_item {
+            let _ = take_handler_options(&mut method.attrs);
+            let _ = take_timer(&mut method.attrs);
         }
     }
+}
+
+/// Removes the `#[interval(...)]` or `#[delayed(...)]` attribute from a method and parses it.
+/// The attribute is removed even if it's invalid.
+pub fn take_timer(attrs: &mut Vec<Attribute>) -> syn::Result<Option<Timer>> {
+    let position = match attrs
+        .iter()
+        .position(|attr| attr.path.is_ident(INTERVAL_ATTR) || attr.path.is_ident(DELAYED_ATTR))
+    {
+        Some(position) => position,
+        None => return Ok(None),
+    };
+    let attr = attrs.remove(position);
+    let (kind, attr_name) = if attr.path.is_ident(INTERVAL_ATTR) {
+        (TimerKind::Interval, INTERVAL_ATTR)
+    } else {
+        (TimerKind::Delayed, DELAYED_ATTR)
+    };
+    let usage = format!(
+        "expected a duration such as #[{0}(secs = 5)] or #[{0}(millis = 500)]",
+        attr_name
+    );
+
+    let list = match attr.parse_meta()? {
+        Meta::List(list) if !list.nested.is_empty() => list,
+        meta => return Err(syn::Error::new_spanned(meta, usage)),
+    };
+    let mut millis: u64 = 0;
+    for nested_meta in &list.nested {
+        let name_value = match nested_meta {
+            NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+            _ => return Err(syn::Error::new_spanned(nested_meta, usage)),
+        };
+        let scale = match name_value.path.to_token_stream().to_string().as_str() {
+            "secs" => 1000,
+            "millis" => 1,
+            key => {
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    format!(
+                        "unknown #[{}] option `{}`, expected `secs` or `millis`",
+                        attr_name, key
+                    ),
+                ))
+            }
+        };
+        let value: u64 = match &name_value.lit {
+            Lit::Int(value) => value.base10_parse()?,
+            lit => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "expected an integer, such as `secs = 5`",
+                ))
+            }
+        };
+        millis = value
+            .checked_mul(scale)
+            .and_then(|value| millis.checked_add(value))
+            .ok_or_else(|| syn::Error::new_spanned(&name_value.lit, "duration is too long"))?;
+    }
+    if kind == TimerKind::Interval && millis == 0 {
+        return Err(syn::Error::new_spanned(
+            &list.nested,
+            "#[interval] needs a nonzero duration",
+        ));
+    }
+
+    Ok(Some(Timer { kind, millis }))
+}
+
+/// Options of `#[actix_handler_macro::test(...)]`
+pub struct TestOptions {
+    pub(crate) actix_path: Path,
+    /// Fail the test if it runs longer than this, never if unset
+    pub(crate) timeout_millis: Option<u64>,
+}
+
+pub fn parse_test_options(args: AttributeArgs) -> syn::Result<TestOptions> {
+    let mut options = TestOptions {
+        actix_path: parse_quote!(::actix),
+        timeout_millis: None,
+    };
+
+    for arg in args {
+        let name_value = match arg {
+            NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+            NestedMeta::Meta(meta) => return Err(unknown_option(TEST_ATTR, meta.path())),
+            NestedMeta::Lit(lit) => {
+                return Err(syn::Error::new_spanned(lit, "expected a #[test] option"))
+            }
+        };
+        let lit = &name_value.lit;
+        let scale = match name_value.path.to_token_stream().to_string().as_str() {
+            "crate" => {
+                options.actix_path = parse_path(lit_str(lit)?)?;
+                continue;
+            }
+            "timeout_secs" => 1000,
+            "timeout_millis" => 1,
+            _ => return Err(unknown_option(TEST_ATTR, &name_value.path)),
+        };
+        let value: u64 = lit_int(lit)?.base10_parse()?;
+        let timeout_millis = options.timeout_millis.get_or_insert(0);
+        *timeout_millis = value
+            .checked_mul(scale)
+            .and_then(|value| timeout_millis.checked_add(value))
+            .ok_or_else(|| syn::Error::new_spanned(lit, "timeout is too long"))?;
+    }
 
-    options
+    Ok(options)
 }
@@ -2,7 +2,89 @@ use std::fmt::Display;
 
 use proc_macro::TokenStream;
 use proc_macro2::Span;
+use quote::{format_ident, ToTokens};
+use syn::{Ident, Path, Type};
 
 pub fn compilation_error<T: Display>(msg: T) -> TokenStream {
     TokenStream::from(syn::Error::new(Span::call_site(), msg).to_compile_error())
 }
+
+/// Adds `err` to the errors collected so far, so they're all reported together
+pub fn push_error(errors: &mut Option<syn::Error>, err: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(err),
+        None => *errors = Some(err),
+    }
+}
+
+/// Compares two paths as written. `syn::Path` only implements `PartialEq` with syn's
+/// `extra-traits` feature, which this crate doesn't enable.
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    a.to_token_stream().to_string() == b.to_token_stream().to_string()
+}
+
+/// Renders a type as it was written in the source, without the spacing `quote` adds
+pub fn type_name_literal(ty: &Type) -> String {
+    ty.to_token_stream().to_string().replace(' ', "")
+}
+
+/// The identifier naming a type, e.g. `Example` for `crate::Example<T>`
+pub fn type_ident(ty: &Type) -> Ident {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.clone())
+            .unwrap_or_else(|| format_ident!("Actor")),
+        _ => format_ident!("Actor"),
+    }
+}
+
+/// Converts a `snake_case` identifier into `CamelCase`
+pub fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Converts a `CamelCase` identifier into `snake_case`
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_camel_case, to_snake_case};
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("greet"), "Greet");
+        assert_eq!(to_camel_case("say_hello"), "SayHello");
+        assert_eq!(to_camel_case("_private__name"), "PrivateName");
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Adder"), "adder");
+        assert_eq!(to_snake_case("RateLimiter"), "rate_limiter");
+    }
+}
@@ -0,0 +1,37 @@
+//! Helpers shared by the integration tests, so they run against every supported actix release.
+//!
+//! Each test crate aliases the actix release matching the enabled `actix-0-*` feature as
+//! `actix` before including this module.
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::time::Duration;
+
+use actix::{System, SystemRunner};
+
+#[cfg(not(any(feature = "actix-0-12", feature = "actix-0-13")))]
+pub fn system() -> SystemRunner {
+    System::new("actix-test-runtime")
+}
+
+#[cfg(any(feature = "actix-0-12", feature = "actix-0-13"))]
+pub fn system() -> SystemRunner {
+    System::new()
+}
+
+/// Runs `future` to completion on a fresh `System`
+#[allow(unused_mut)]
+pub fn block_on<F: Future + 'static>(future: F) -> F::Output {
+    let mut sys = system();
+    sys.block_on(future)
+}
+
+#[cfg(not(any(feature = "actix-0-12", feature = "actix-0-13")))]
+pub async fn sleep(duration: Duration) {
+    actix::clock::delay_for(duration).await
+}
+
+#[cfg(any(feature = "actix-0-12", feature = "actix-0-13"))]
+pub async fn sleep(duration: Duration) {
+    actix::clock::sleep(duration).await
+}
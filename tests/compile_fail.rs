@@ -0,0 +1,7 @@
+//! Attribute mistakes that must be reported as errors rather than silently ignored
+
+#[test]
+fn test_compile_fail() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
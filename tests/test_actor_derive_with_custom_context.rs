@@ -1,7 +1,13 @@
-use actix::{Actor, Handler, System};
-use actix_derive::{Message, MessageResponse};
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Handler, Message, MessageResponse, SyncArbiter};
 use actix_handler_macro::Actor;
 
+mod common;
+
 #[derive(MessageResponse)]
 struct Added(usize);
 
@@ -13,6 +19,14 @@ struct Sum(usize, usize);
 #[actor(context = "::actix::SyncContext")]
 struct Adder;
 
+#[derive(Actor, Default)]
+#[actor(context = "SyncContext", threads = 2)]
+struct PooledAdder;
+
+#[derive(Actor)]
+#[actor(arbiter = "new")]
+struct Offset(usize);
+
 impl Handler<Sum> for Adder {
     type Result = <Sum as actix::Message>::Result;
     fn handle(&mut self, msg: Sum, _: &mut Self::Context) -> Added {
@@ -20,10 +34,49 @@ impl Handler<Sum> for Adder {
     }
 }
 
+impl Handler<Sum> for PooledAdder {
+    type Result = <Sum as actix::Message>::Result;
+    fn handle(&mut self, msg: Sum, _: &mut Self::Context) -> Added {
+        Added(msg.0 + msg.1)
+    }
+}
+
+impl Handler<Sum> for Offset {
+    type Result = <Sum as actix::Message>::Result;
+    fn handle(&mut self, msg: Sum, _: &mut Self::Context) -> Added {
+        Added(self.0 + msg.0 + msg.1)
+    }
+}
+
 #[test]
 fn test_message() {
-    let mut sys = System::new("actix-test-runtime");
-    let addr = Adder::start_default();
-    let res = sys.block_on(addr.send(Sum(3, 5))).unwrap();
+    let res = common::block_on(async move {
+        let addr = SyncArbiter::start(1, Adder::default);
+        addr.send(Sum(3, 5)).await
+    })
+    .unwrap();
     assert_eq!(res.0, 8);
 }
+
+#[test]
+fn test_start_sync_pool() {
+    let res = common::block_on(async move {
+        let addr = PooledAdder::start_sync_pool();
+        let replies = futures::future::join_all((0..4).map(|i| addr.send(Sum(i, 1)))).await;
+        replies
+            .into_iter()
+            .map(|reply| reply.unwrap().0)
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(res, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_start_on_new_arbiter() {
+    let res = common::block_on(async move {
+        let addr = Offset(10).start_on_new_arbiter();
+        addr.send(Sum(3, 5)).await
+    })
+    .unwrap();
+    assert_eq!(res.0, 18);
+}
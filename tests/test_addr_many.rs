@@ -0,0 +1,90 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::cell::Cell;
+
+use actix::{Actor, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+thread_local! {
+    static SENT: Cell<usize> = const { Cell::new(0) };
+    static HANDLED: Cell<usize> = const { Cell::new(0) };
+    static MAX_IN_FLIGHT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Builds the messages lazily, counting each one as sent when the `_many` method pulls it
+fn doubles(n: usize) -> impl Iterator<Item = Double> {
+    (0..n).map(|i| {
+        SENT.with(|sent| sent.set(sent.get() + 1));
+        Double(i)
+    })
+}
+
+/// Records how many messages have been sent but not handled yet, this one included
+fn record_in_flight() {
+    let in_flight = SENT.with(Cell::get) - HANDLED.with(Cell::get);
+    MAX_IN_FLIGHT.with(|max| max.set(max.get().max(in_flight)));
+    HANDLED.with(|handled| handled.set(handled.get() + 1));
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Double(usize);
+
+#[derive(Actor)]
+struct Example;
+
+#[actix_handler]
+impl Example {
+    fn double(&self, message: Double, _ctx: &actix::Context<Self>) -> usize {
+        record_in_flight();
+        message.0 * 2
+    }
+}
+
+#[derive(Actor)]
+struct Bounded;
+
+#[actix_handler(use_recipient, max_in_flight = 2)]
+impl Bounded {
+    fn double(&self, message: Double, _ctx: &actix::Context<Self>) -> usize {
+        record_in_flight();
+        message.0 * 2
+    }
+}
+
+#[test]
+fn test_many() {
+    let results = common::block_on(async move {
+        let addr = Example {}.start();
+        addr.double_many(doubles(5)).await
+    });
+    let results: Vec<usize> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(results, vec![0, 2, 4, 6, 8]);
+    // Without a bound every message is sent before the first reply is awaited
+    assert_eq!(MAX_IN_FLIGHT.with(Cell::get), 5);
+}
+
+#[test]
+fn test_many_with_max_in_flight() {
+    let results = common::block_on(async move {
+        let addr = Bounded {}.start();
+        addr.double_many(doubles(5)).await
+    });
+    let results: Vec<usize> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(results, vec![0, 2, 4, 6, 8]);
+    assert_eq!(MAX_IN_FLIGHT.with(Cell::get), 2);
+}
+
+#[test]
+fn test_addr_trait_object() {
+    let result = common::block_on(async move {
+        let addr: Box<dyn ExampleAddr> = Box::new(Example {}.start());
+        addr.double(Double(21)).await
+    });
+    assert_eq!(result.unwrap(), 42);
+}
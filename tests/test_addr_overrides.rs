@@ -0,0 +1,65 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Addr, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Greeting;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Count;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Flush;
+
+#[actix_handler(addr_prefix = "send_", addr_suffix = "_request")]
+impl Example {
+    fn greet(&self, _message: Greeting, _ctx: &actix::Context<Self>) -> String {
+        "Hello".to_string()
+    }
+
+    #[handler(addr_name = "count")]
+    fn handle_count(&self, _message: Count, _ctx: &actix::Context<Self>) -> usize {
+        1
+    }
+
+    #[handler(no_addr)]
+    fn flush(&self, _message: Flush, _ctx: &actix::Context<Self>) {}
+}
+
+/// Only the methods left on the trait need to be implemented
+struct ExampleForwarder(Addr<Example>);
+
+impl ExampleAddr for ExampleForwarder {
+    fn send_greet_request(&self, msg: Greeting) -> actix::prelude::Request<Example, Greeting> {
+        self.0.send(msg)
+    }
+
+    fn count(&self, msg: Count) -> actix::prelude::Request<Example, Count> {
+        self.0.send(msg)
+    }
+}
+
+#[test]
+fn test_renamed_addr_methods() {
+    let (greeting, count) = common::block_on(async move {
+        let addr = Example {}.start();
+        addr.send(Flush).await.unwrap();
+        let greeting = addr.send_greet_request(Greeting).await.unwrap();
+        let count = ExampleForwarder(addr).count(Count).await.unwrap();
+        (greeting, count)
+    });
+    assert_eq!(greeting, "Hello");
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,147 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+use actix_handler_sim as sim;
+
+#[derive(Actor, Default)]
+struct Config {
+    values: HashMap<String, String>,
+    lookups: usize,
+    cache: ConfigHandlerCache,
+}
+
+#[derive(Message)]
+#[rtype(result = "Option<String>")]
+struct GetValue {
+    key: String,
+    // Not part of the cache key
+    _request_id: usize,
+}
+
+#[derive(Message, Clone, Hash, PartialEq, Eq)]
+#[rtype(result = "usize")]
+struct CountPrefix(String);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct SetValue(String, String);
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct GetLookups;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct GetCachedPrefixes;
+
+#[actix_handler]
+impl Config {
+    #[handler(cache(key = "key"))]
+    fn get_value(&mut self, msg: GetValue, _ctx: &mut Context<Self>) -> Option<String> {
+        self.lookups += 1;
+        self.values.get(&msg.key).cloned()
+    }
+
+    #[handler(cache(ttl_secs = 1))]
+    fn count_prefix(&mut self, msg: CountPrefix, _ctx: &mut Context<Self>) -> usize {
+        self.lookups += 1;
+        self.values
+            .keys()
+            .filter(|key| key.starts_with(&msg.0))
+            .count()
+    }
+
+    #[invalidates(get_value, count_prefix)]
+    fn set_value(&mut self, msg: SetValue, _ctx: &mut Context<Self>) {
+        self.values.insert(msg.0, msg.1);
+    }
+
+    fn get_lookups(&self, _msg: GetLookups, _ctx: &Context<Self>) -> usize {
+        self.lookups
+    }
+
+    fn get_cached_prefixes(&self, _msg: GetCachedPrefixes, _ctx: &Context<Self>) -> usize {
+        self.cache.count_prefix.len()
+    }
+}
+
+fn get(key: &str, request_id: usize) -> GetValue {
+    GetValue {
+        key: key.to_string(),
+        _request_id: request_id,
+    }
+}
+
+#[test]
+fn test_cached_replies_until_invalidated() {
+    let (first, second, after_set, lookups) = common::block_on(async move {
+        let addr = Config::default().start();
+        addr.set_value(SetValue("color".to_string(), "red".to_string()))
+            .await
+            .unwrap();
+        let first = addr.get_value(get("color", 1)).await.unwrap();
+        let second = addr.get_value(get("color", 2)).await.unwrap();
+        addr.get_value(get("size", 3)).await.unwrap();
+        addr.set_value(SetValue("color".to_string(), "blue".to_string()))
+            .await
+            .unwrap();
+        let after_set = addr.get_value(get("color", 4)).await.unwrap();
+        let lookups = addr.get_lookups(GetLookups).await.unwrap();
+        (first, second, after_set, lookups)
+    });
+    assert_eq!(first.as_deref(), Some("red"));
+    assert_eq!(second.as_deref(), Some("red"));
+    assert_eq!(after_set.as_deref(), Some("blue"));
+    // "color" twice, once before and once after the update, and "size" once
+    assert_eq!(lookups, 3);
+}
+
+#[test]
+fn test_cached_replies_expire() {
+    let (before_expiry, after_expiry, cached) = common::block_on(async move {
+        sim::pause();
+        let addr = Config::default().start();
+        let prefix = || CountPrefix("c".to_string());
+        addr.count_prefix(prefix()).await.unwrap();
+        addr.count_prefix(prefix()).await.unwrap();
+        let before_expiry = addr.get_lookups(GetLookups).await.unwrap();
+        sim::advance(Duration::from_secs(1)).await;
+        addr.count_prefix(prefix()).await.unwrap();
+        let after_expiry = addr.get_lookups(GetLookups).await.unwrap();
+        let cached = addr.get_cached_prefixes(GetCachedPrefixes).await.unwrap();
+        (before_expiry, after_expiry, cached)
+    });
+    // The second call is answered from the cache, the third one isn't
+    assert_eq!(before_expiry, 1);
+    assert_eq!(after_expiry, 2);
+    assert_eq!(cached, 1);
+}
+
+#[test]
+fn test_expired_replies_are_evicted_on_insert() {
+    let cached = common::block_on(async move {
+        sim::pause();
+        let addr = Config::default().start();
+        for prefix in ["a", "b", "c"] {
+            addr.count_prefix(CountPrefix(prefix.to_string()))
+                .await
+                .unwrap();
+        }
+        sim::advance(Duration::from_secs(1)).await;
+        addr.count_prefix(CountPrefix("d".to_string()))
+            .await
+            .unwrap();
+        addr.get_cached_prefixes(GetCachedPrefixes).await.unwrap()
+    });
+    assert_eq!(cached, 1);
+}
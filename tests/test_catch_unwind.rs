@@ -0,0 +1,71 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::any::Any;
+use std::cell::RefCell;
+
+use actix::{Actor, MailboxError, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+thread_local! {
+    static PANICS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record_panic(actor: &'static str, method: &'static str, _payload: &(dyn Any + Send)) {
+    PANICS.with(|panics| panics.borrow_mut().push(format!("{}::{}", actor, method)));
+}
+
+fn panic_to_error(_payload: Box<dyn Any + Send>) -> Result<String, String> {
+    Err("handler panicked".to_string())
+}
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message)]
+#[rtype(result = "Result<String, String>")]
+struct Divide(u32, u32);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Explode;
+
+#[actix_handler(panic_hook = "record_panic")]
+impl Example {
+    #[handler(catch_unwind = "panic_to_error")]
+    fn divide(&self, message: Divide, _ctx: &actix::Context<Self>) -> Result<String, String> {
+        Ok(format!("{}", message.0 / message.1))
+    }
+
+    #[handler(catch_unwind)]
+    fn explode(&self, _message: Explode, _ctx: &actix::Context<Self>) {
+        panic!("boom");
+    }
+}
+
+#[test]
+fn test_panic_is_converted_into_result() {
+    common::block_on(async move {
+        let addr = Example {}.start();
+        let result = addr.divide(Divide(4, 0)).await.unwrap();
+        assert_eq!(result, Err("handler panicked".to_string()));
+        let result = addr.divide(Divide(4, 2)).await.unwrap();
+        assert_eq!(result, Ok("2".to_string()));
+    });
+    let panics = PANICS.with(|panics| panics.borrow().clone());
+    assert_eq!(panics, vec!["Example::divide"]);
+}
+
+#[test]
+fn test_panic_stops_actor() {
+    common::block_on(async move {
+        let addr = Example {}.start();
+        addr.do_send(Explode);
+        let reply = addr.divide(Divide(4, 2)).await;
+        assert!(matches!(reply, Err(MailboxError::Closed)), "{:?}", reply);
+    });
+}
@@ -0,0 +1,97 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix_handler_macro::{Actor, Supervised};
+
+mod common;
+
+/// actix re-exported under another name
+mod runtime {
+    pub use actix::*;
+}
+
+mod reexported {
+    use actix_handler_macro::{actix_handler, Actor};
+
+    use crate::runtime::{Actor, Message};
+
+    #[derive(Actor)]
+    #[actor(crate = "crate::runtime")]
+    pub struct Example;
+
+    #[derive(Message)]
+    #[rtype(result = "String")]
+    pub struct Greeting;
+
+    #[actix_handler(crate = "crate::runtime", message_enum = "ExampleMsg")]
+    impl Example {
+        fn greet(&self, _message: Greeting, _ctx: &crate::runtime::Context<Self>) -> String {
+            "Hello".to_string()
+        }
+    }
+
+    pub async fn greet() -> String {
+        let addr = Example.start();
+        addr.greet(Greeting).await.unwrap()
+    }
+
+    pub async fn greet_through_enum() -> String {
+        let addr = Example.start();
+        match addr.send(ExampleMsg::from(Greeting)).await.unwrap() {
+            ExampleMsgResult::Greet(greeting) => greeting,
+        }
+    }
+}
+
+/// A local module shadowing the actix crate name
+mod shadowed {
+    #[allow(dead_code)]
+    mod actix {}
+
+    use ::actix::{Actor, Message};
+    use actix_handler_macro::{actix_handler, Actor, Supervised};
+
+    #[derive(Actor, Supervised)]
+    pub struct Example;
+
+    #[derive(Message)]
+    #[rtype(result = "String")]
+    pub struct Greeting;
+
+    #[actix_handler]
+    impl Example {
+        fn greet(&self, _message: Greeting, _ctx: &::actix::Context<Self>) -> String {
+            "Hello".to_string()
+        }
+    }
+
+    pub async fn greet() -> String {
+        let addr = Example.start();
+        addr.greet(Greeting).await.unwrap()
+    }
+}
+
+#[derive(Actor, Supervised)]
+#[actor(crate = "crate::runtime")]
+struct Restartable;
+
+#[test]
+fn test_reexported_crate_path() {
+    assert_eq!(common::block_on(reexported::greet()), "Hello");
+    assert_eq!(common::block_on(reexported::greet_through_enum()), "Hello");
+}
+
+#[test]
+fn test_shadowed_crate_name() {
+    assert_eq!(common::block_on(shadowed::greet()), "Hello");
+}
+
+#[test]
+fn test_supervised_with_crate_path() {
+    common::block_on(async move {
+        let addr = actix::Supervisor::start(|_| Restartable);
+        assert!(addr.connected());
+    });
+}
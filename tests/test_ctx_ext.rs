@@ -0,0 +1,87 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::time::Duration;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Actor, Default)]
+struct Counter {
+    count: usize,
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Start;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Tick;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct GetCount;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Reset;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Cleared;
+
+#[actix_handler(ctx_ext)]
+impl Counter {
+    fn start(&mut self, _message: Start, ctx: &mut Context<Self>) {
+        ctx.notify_tick(Tick);
+        ctx.notify_tick_later(Tick, Duration::from_millis(10));
+        ctx.run_tick_interval(Duration::from_millis(5), |_counter| Tick);
+    }
+
+    fn tick(&mut self, _message: Tick, _ctx: &mut Context<Self>) {
+        self.count += 1;
+    }
+
+    fn get_count(&self, _message: GetCount, _ctx: &Context<Self>) -> usize {
+        self.count
+    }
+}
+
+// A second block on the same actor gets its own helpers, named after its trait
+#[actix_handler(ctx_ext, trait_name = "CounterAdmin")]
+impl Counter {
+    fn reset(&mut self, _message: Reset, ctx: &mut Context<Self>) {
+        ctx.notify_cleared(Cleared);
+    }
+
+    fn cleared(&mut self, _message: Cleared, _ctx: &mut Context<Self>) {
+        self.count = 0;
+    }
+}
+
+#[test]
+fn test_ctx_ext_notifications() {
+    let count = common::block_on(async move {
+        let addr = Counter::default().start();
+        addr.start(Start).await.unwrap();
+        common::sleep(Duration::from_millis(100)).await;
+        addr.get_count(GetCount).await.unwrap()
+    });
+    assert!(count >= 3, "expected at least 3 ticks, got {}", count);
+}
+
+#[test]
+fn test_ctx_ext_per_block() {
+    let count = common::block_on(async move {
+        let addr = Counter { count: 5 }.start();
+        addr.reset(Reset).await.unwrap();
+        common::sleep(Duration::from_millis(10)).await;
+        addr.get_count(GetCount).await.unwrap()
+    });
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,162 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Debug, PartialEq)]
+enum AccountError {
+    Forbidden(&'static str),
+    Missing,
+}
+
+struct Forbidden(&'static str);
+
+impl From<Forbidden> for AccountError {
+    fn from(forbidden: Forbidden) -> Self {
+        AccountError::Forbidden(forbidden.0)
+    }
+}
+
+trait Caller {
+    fn caller(&self) -> &str;
+}
+
+fn signed_in<M: Caller>(_accounts: &Accounts, msg: &M) -> Result<(), Forbidden> {
+    if msg.caller().is_empty() {
+        Err(Forbidden("not signed in"))
+    } else {
+        Ok(())
+    }
+}
+
+fn admin_only(accounts: &Accounts, msg: &DeleteAccount) -> Result<(), Forbidden> {
+    if msg.caller == accounts.admin {
+        Ok(())
+    } else {
+        Err(Forbidden("admin only"))
+    }
+}
+
+#[derive(Actor)]
+struct Accounts {
+    admin: String,
+    names: Vec<String>,
+}
+
+#[derive(Message)]
+#[rtype(result = "Result<usize, AccountError>")]
+struct CountAccounts {
+    caller: String,
+}
+
+impl Caller for CountAccounts {
+    fn caller(&self) -> &str {
+        &self.caller
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "Result<(), AccountError>")]
+struct DeleteAccount {
+    caller: String,
+    name: String,
+}
+
+#[derive(Message)]
+#[rtype(result = "Result<bool, AccountError>")]
+struct IsOpen;
+
+#[actix_handler(guard = "signed_in")]
+impl Accounts {
+    fn count_accounts(
+        &self,
+        _msg: CountAccounts,
+        _ctx: &Context<Self>,
+    ) -> Result<usize, AccountError> {
+        Ok(self.names.len())
+    }
+
+    #[handler(guard = "admin_only")]
+    fn delete_account(
+        &mut self,
+        msg: DeleteAccount,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), AccountError> {
+        let position = self
+            .names
+            .iter()
+            .position(|name| name == &msg.name)
+            .ok_or(AccountError::Missing)?;
+        self.names.remove(position);
+        Ok(())
+    }
+
+    #[handler(no_guard)]
+    fn is_open(&self, _msg: IsOpen, _ctx: &Context<Self>) -> Result<bool, AccountError> {
+        Ok(true)
+    }
+}
+
+fn accounts() -> Accounts {
+    Accounts {
+        admin: "root".to_string(),
+        names: vec!["alice".to_string(), "bob".to_string()],
+    }
+}
+
+#[test]
+fn test_impl_level_guard() {
+    let (anonymous, signed_in, open) = common::block_on(async move {
+        let addr = accounts().start();
+        let anonymous = addr.count_accounts(CountAccounts {
+            caller: String::new(),
+        });
+        let signed_in = addr.count_accounts(CountAccounts {
+            caller: "alice".to_string(),
+        });
+        (
+            anonymous.await.unwrap(),
+            signed_in.await.unwrap(),
+            addr.is_open(IsOpen).await.unwrap(),
+        )
+    });
+    assert_eq!(anonymous, Err(AccountError::Forbidden("not signed in")));
+    assert_eq!(signed_in, Ok(2));
+    assert_eq!(open, Ok(true));
+}
+
+#[test]
+fn test_handler_guard_overrides_impl_guard() {
+    let (denied, allowed, remaining) = common::block_on(async move {
+        let addr = accounts().start();
+        let denied = addr
+            .delete_account(DeleteAccount {
+                caller: "alice".to_string(),
+                name: "bob".to_string(),
+            })
+            .await
+            .unwrap();
+        let allowed = addr
+            .delete_account(DeleteAccount {
+                caller: "root".to_string(),
+                name: "bob".to_string(),
+            })
+            .await
+            .unwrap();
+        let remaining = addr
+            .count_accounts(CountAccounts {
+                caller: "root".to_string(),
+            })
+            .await
+            .unwrap();
+        (denied, allowed, remaining)
+    });
+    assert_eq!(denied, Err(AccountError::Forbidden("admin only")));
+    assert_eq!(allowed, Ok(()));
+    assert_eq!(remaining, Ok(1));
+}
@@ -0,0 +1,59 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::any::TypeId;
+
+use actix::{Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+pub struct HandlerInfo {
+    pub method: &'static str,
+    pub message: &'static str,
+    pub result: &'static str,
+    pub message_type_id: fn() -> TypeId,
+    pub result_type_id: fn() -> TypeId,
+}
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Greeting {
+    name: String,
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Count;
+
+#[actix_handler(handler_info = "HandlerInfo")]
+impl Example {
+    fn greet(&self, message: Greeting, _ctx: &Context<Self>) -> String {
+        format!("hello {}", message.name)
+    }
+
+    fn count(&self, _message: Count, _ctx: &Context<Self>) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_handlers_are_listed() {
+    let handlers = Example::HANDLERS;
+    assert_eq!(handlers.len(), 2);
+
+    assert_eq!(handlers[0].method, "greet");
+    assert_eq!(handlers[0].message, "Greeting");
+    assert_eq!(handlers[0].result, "String");
+    assert_eq!((handlers[0].message_type_id)(), TypeId::of::<Greeting>());
+    assert_eq!((handlers[0].result_type_id)(), TypeId::of::<String>());
+
+    assert_eq!(handlers[1].method, "count");
+    assert_eq!(handlers[1].message, "Count");
+    assert_eq!((handlers[1].result_type_id)(), TypeId::of::<usize>());
+}
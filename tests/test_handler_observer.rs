@@ -0,0 +1,198 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use actix::{Actor, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+thread_local! {
+    static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+struct TestObserver;
+
+impl TestObserver {
+    fn before_handle(actor: &'static str, method: &'static str, message: &'static str) {
+        EVENTS.with(|events| {
+            events
+                .borrow_mut()
+                .push(format!("before {} {} {}", actor, method, message))
+        });
+    }
+
+    fn after_handle(
+        actor: &'static str,
+        method: &'static str,
+        message: &'static str,
+        _elapsed: Duration,
+    ) {
+        EVENTS.with(|events| {
+            events
+                .borrow_mut()
+                .push(format!("after {} {} {}", actor, method, message))
+        });
+    }
+}
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Greeting {
+    name: String,
+}
+
+#[actix_handler(observer = "TestObserver")]
+impl Example {
+    fn greet(&self, message: Greeting, _ctx: &actix::Context<Self>) -> String {
+        EVENTS.with(|events| events.borrow_mut().push("greet".to_string()));
+        format!("Hello {}", message.name)
+    }
+}
+
+#[test]
+fn test_observer_is_called_around_handler() {
+    let result = common::block_on(async move {
+        let addr = Example {}.start();
+        addr.greet(Greeting {
+            name: "you".to_string(),
+        })
+        .await
+        .ok()
+        .unwrap()
+    });
+    assert_eq!(result, "Hello you");
+
+    let events = EVENTS.with(|events| events.borrow().clone());
+    assert_eq!(
+        events,
+        vec![
+            "before Example greet Greeting",
+            "greet",
+            "after Example greet Greeting"
+        ]
+    );
+}
+
+#[cfg(feature = "metrics")]
+mod metrics_backend {
+    use std::sync::{Arc, Mutex};
+
+    use actix::{Actor, Message};
+    use actix_handler_macro::{actix_handler, Actor};
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+
+    use crate::common;
+
+    /// Records every counter increment and histogram sample as `name{labels} value`
+    #[derive(Clone, Default)]
+    struct TestRecorder {
+        samples: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Sample {
+        key: Key,
+        samples: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Sample {
+        fn push(&self, value: String) {
+            let labels: Vec<String> = self
+                .key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            self.samples.lock().unwrap().push(format!(
+                "{}{{{}}} {}",
+                self.key.name(),
+                labels.join(","),
+                value
+            ));
+        }
+    }
+
+    impl CounterFn for Sample {
+        fn increment(&self, value: u64) {
+            self.push(format!("+{}", value));
+        }
+
+        fn absolute(&self, value: u64) {
+            self.push(format!("={}", value));
+        }
+    }
+
+    impl HistogramFn for Sample {
+        fn record(&self, value: f64) {
+            self.push(if value >= 0.0 { "seconds" } else { "negative" }.to_string());
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(Arc::new(Sample {
+                key: key.clone(),
+                samples: self.samples.clone(),
+            }))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(Arc::new(Sample {
+                key: key.clone(),
+                samples: self.samples.clone(),
+            }))
+        }
+    }
+
+    #[derive(Actor)]
+    struct Measured;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Ping;
+
+    #[actix_handler(metrics)]
+    impl Measured {
+        fn ping(&self, _message: Ping, _ctx: &actix::Context<Self>) {}
+    }
+
+    #[test]
+    fn test_metrics_backend() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            common::block_on(async move {
+                let addr = Measured {}.start();
+                addr.ping(Ping {}).await.unwrap();
+                addr.ping(Ping {}).await.unwrap();
+            })
+        });
+
+        let labels = "actor=Measured,method=ping,message=Ping";
+        assert_eq!(
+            *recorder.samples.lock().unwrap(),
+            vec![
+                format!("actix_handler_calls_total{{{}}} +1", labels),
+                format!("actix_handler_duration_seconds{{{}}} seconds", labels),
+                format!("actix_handler_calls_total{{{}}} +1", labels),
+                format!("actix_handler_duration_seconds{{{}}} seconds", labels),
+            ]
+        );
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+#[derive(Actor, Default)]
+struct Greeter {
+    greeted: Vec<String>,
+}
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Greeting {
+    name: String,
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct CountGreeted;
+
+#[actix_handler(harness)]
+impl Greeter {
+    fn greet(&mut self, msg: Greeting, _ctx: &mut Context<Self>) -> String {
+        self.greeted.push(msg.name.clone());
+        format!("Hello {}", msg.name)
+    }
+
+    fn count_greeted(&self, _msg: CountGreeted, _ctx: &Context<Self>) -> usize {
+        self.greeted.len()
+    }
+}
+
+#[test]
+fn test_harness_calls_handlers_without_a_system() {
+    let mut harness = GreeterHarness::new(Greeter::default());
+    let greeting = harness.greet(Greeting {
+        name: "Alice".to_string(),
+    });
+    assert_eq!(greeting, "Hello Alice");
+    assert_eq!(harness.count_greeted(CountGreeted), 1);
+    assert_eq!(harness.actor.greeted, vec!["Alice".to_string()]);
+}
@@ -0,0 +1,109 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, actix_protocol, Actor};
+
+mod common;
+
+#[actix_protocol]
+trait Greeter {
+    fn greet(&self, name: String) -> String;
+}
+
+#[actix_protocol]
+trait Counter {
+    fn increment(&self) -> usize;
+}
+
+#[derive(Actor, Default)]
+struct Receptionist {
+    visitors: usize,
+}
+
+#[actix_handler(implements = "Greeter, Counter")]
+impl Receptionist {
+    #[handler(implements = "Greeter")]
+    fn greet(&self, msg: Greet, _ctx: &Context<Self>) -> String {
+        format!("Welcome {}", msg.name)
+    }
+
+    #[handler(implements = "Counter")]
+    fn increment(&mut self, _msg: Increment, _ctx: &mut Context<Self>) -> usize {
+        self.visitors += 1;
+        self.visitors
+    }
+}
+
+#[test]
+fn test_several_protocols() {
+    let (greeting, visitors) = common::block_on(async move {
+        let addr = Receptionist::default().start();
+        let greeting = addr.greet("Alice".to_string()).await.unwrap();
+        addr.increment().await.unwrap();
+        let visitors = addr.increment().await.unwrap();
+        (greeting, visitors)
+    });
+    assert_eq!(greeting, "Welcome Alice");
+    assert_eq!(visitors, 2);
+}
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Ping;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Load;
+
+#[derive(Actor)]
+struct Primary;
+
+#[actix_handler(use_recipient, trait_name = "PingAddr")]
+impl Primary {
+    fn ping(&self, _msg: Ping, _ctx: &Context<Self>) -> String {
+        "primary".to_string()
+    }
+}
+
+#[derive(Actor)]
+struct LoadReporter;
+
+#[actix_handler(use_recipient, trait_name = "LoadAddr")]
+impl LoadReporter {
+    fn load(&self, _msg: Load, _ctx: &Context<Self>) -> usize {
+        0
+    }
+}
+
+#[derive(Actor)]
+struct Replica;
+
+#[actix_handler(use_recipient, no_trait_decl, implements = "PingAddr, LoadAddr")]
+impl Replica {
+    #[handler(implements = "PingAddr")]
+    fn ping(&self, _msg: Ping, _ctx: &Context<Self>) -> String {
+        "replica".to_string()
+    }
+
+    #[handler(implements = "LoadAddr")]
+    fn load(&self, _msg: Load, _ctx: &Context<Self>) -> usize {
+        7
+    }
+}
+
+#[test]
+fn test_several_addr_traits() {
+    let (primary, replica, load) = common::block_on(async move {
+        let primary = Primary.start().ping(Ping).await.unwrap();
+        let addr = Replica.start();
+        let replica = addr.ping(Ping).await.unwrap();
+        let load = addr.load(Load).await.unwrap() + LoadReporter.start().load(Load).await.unwrap();
+        (primary, replica, load)
+    });
+    assert_eq!(primary, "primary");
+    assert_eq!(replica, "replica");
+    assert_eq!(load, 7);
+}
@@ -0,0 +1,179 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::cell::RefCell;
+
+use actix::{Actor, Context, MailboxError, Message};
+use actix_handler_macro::{actix_handler, Actor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Actor, Default)]
+struct Ledger {
+    balance: i64,
+    journal: Option<Box<dyn LedgerJournal>>,
+}
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "i64")]
+struct Deposit {
+    amount: i64,
+}
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "i64")]
+struct Withdraw {
+    amount: i64,
+}
+
+#[derive(Message)]
+#[rtype(result = "i64")]
+struct GetBalance;
+
+#[actix_handler(journal)]
+impl Ledger {
+    #[handler(persist)]
+    fn deposit(&mut self, msg: Deposit, _ctx: &mut Context<Self>) -> i64 {
+        self.balance += msg.amount;
+        self.balance
+    }
+
+    #[handler(persist)]
+    fn withdraw(&mut self, msg: Withdraw, _ctx: &mut Context<Self>) -> i64 {
+        self.balance -= msg.amount;
+        self.balance
+    }
+
+    fn get_balance(&self, _msg: GetBalance, _ctx: &mut Context<Self>) -> i64 {
+        self.balance
+    }
+}
+
+thread_local! {
+    static JOURNAL_ERRORS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record_journal_error(actor: &'static str, method: &'static str, error: &std::io::Error) {
+    JOURNAL_ERRORS.with(|errors| {
+        errors
+            .borrow_mut()
+            .push(format!("{}::{}: {}", actor, method, error))
+    });
+}
+
+/// A journal whose disk is full
+struct FullJournal;
+
+impl CounterJournal for FullJournal {
+    fn append(&mut self, _entry: &str) -> std::io::Result<()> {
+        Err(std::io::Error::other("disk full"))
+    }
+
+    fn entries(&self) -> std::io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Actor, Default)]
+struct Counter {
+    count: u32,
+    journal: Option<Box<dyn CounterJournal>>,
+}
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "u32")]
+struct Increment;
+
+#[actix_handler(journal, journal_error = "record_journal_error")]
+impl Counter {
+    #[handler(persist)]
+    fn increment(&mut self, _msg: Increment, _ctx: &mut Context<Self>) -> u32 {
+        self.count += 1;
+        self.count
+    }
+}
+
+fn ledger(journal: impl LedgerJournal + 'static) -> Ledger {
+    Ledger {
+        journal: Some(Box::new(journal)),
+        ..Ledger::default()
+    }
+}
+
+#[actix_handler_macro::test]
+async fn test_persists_marked_messages() {
+    let journal = LedgerMemoryJournal::default();
+    let addr = ledger(journal.clone()).start();
+
+    addr.deposit(Deposit { amount: 100 }).await.unwrap();
+    addr.get_balance(GetBalance).await.unwrap();
+    addr.withdraw(Withdraw { amount: 30 }).await.unwrap();
+
+    assert_eq!(
+        journal.entries().unwrap(),
+        vec![
+            r#"["deposit",{"amount":100}]"#.to_string(),
+            r#"["withdraw",{"amount":30}]"#.to_string(),
+        ]
+    );
+}
+
+#[actix_handler_macro::test]
+async fn test_replay_rebuilds_state() {
+    let journal = LedgerMemoryJournal::default();
+    let addr = ledger(journal.clone()).start();
+    addr.deposit(Deposit { amount: 100 }).await.unwrap();
+    addr.withdraw(Withdraw { amount: 30 }).await.unwrap();
+
+    let addr = Ledger::replay(journal.clone()).unwrap().start();
+    assert_eq!(addr.get_balance(GetBalance).await.unwrap(), 70);
+
+    // Replaying doesn't append again, but the rebuilt actor keeps recording
+    assert_eq!(journal.entries().unwrap().len(), 2);
+    addr.deposit(Deposit { amount: 5 }).await.unwrap();
+    assert_eq!(journal.entries().unwrap().len(), 3);
+}
+
+#[actix_handler_macro::test]
+async fn test_file_journal() {
+    let path = std::env::temp_dir().join(format!("ledger-journal-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let addr = ledger(LedgerFileJournal::open(&path).unwrap()).start();
+    addr.deposit(Deposit { amount: 40 }).await.unwrap();
+    addr.withdraw(Withdraw { amount: 15 }).await.unwrap();
+
+    let replayed = Ledger::replay(LedgerFileJournal::open(&path).unwrap()).unwrap();
+    assert_eq!(replayed.balance, 25);
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_replay_rejects_unknown_entries() {
+    let mut journal = LedgerMemoryJournal::default();
+    journal.append(r#"["get_balance",null]"#).unwrap();
+
+    let err = Ledger::replay(journal).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[actix_handler_macro::test]
+async fn test_journal_errors_stop_the_actor() {
+    let addr = Counter {
+        journal: Some(Box::new(FullJournal)),
+        ..Counter::default()
+    }
+    .start();
+
+    // The message is still handled, but nothing else is applied without being recorded
+    assert_eq!(addr.increment(Increment).await.unwrap(), 1);
+    let reply = addr.increment(Increment).await;
+    assert!(matches!(reply, Err(MailboxError::Closed)), "{:?}", reply);
+    assert_eq!(
+        JOURNAL_ERRORS.with(|errors| errors.borrow().clone()),
+        vec!["Counter::increment: disk full"]
+    );
+}
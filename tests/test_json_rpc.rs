@@ -0,0 +1,96 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Message};
+use actix_handler_macro::{actix_handler, Actor};
+use serde::Deserialize;
+use serde_json::json;
+
+mod common;
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message, Deserialize)]
+#[rtype(result = "String")]
+struct Greeting {
+    name: String,
+}
+
+#[derive(Message, Deserialize)]
+#[rtype(result = "usize")]
+struct CountLetters(String);
+
+#[actix_handler(json_rpc)]
+impl Example {
+    fn greet(&self, message: Greeting, _ctx: &actix::Context<Self>) -> String {
+        format!("Hello {}", message.name)
+    }
+
+    fn count_letters(&self, message: CountLetters, _ctx: &actix::Context<Self>) -> usize {
+        message.0.len()
+    }
+}
+
+#[test]
+fn test_dispatch_json() {
+    common::block_on(async move {
+        let addr = Example {}.start();
+        let result = addr.dispatch_json("greet", json!({ "name": "you" })).await;
+        assert_eq!(result.unwrap(), json!("Hello you"));
+
+        let result = addr.dispatch_json("count_letters", json!("four")).await;
+        assert_eq!(result.unwrap(), json!(4));
+    });
+}
+
+#[test]
+fn test_dispatch_json_errors() {
+    common::block_on(async move {
+        let addr = Example {}.start();
+        let err = addr.dispatch_json("shout", json!({})).await.unwrap_err();
+        assert!(
+            matches!(err, ExampleJsonRpcError::MethodNotFound(ref method) if method == "shout")
+        );
+        assert_eq!(err.code(), -32601);
+
+        let err = addr.dispatch_json("greet", json!(4)).await.unwrap_err();
+        assert!(matches!(err, ExampleJsonRpcError::InvalidParams(_)));
+        assert_eq!(err.code(), -32602);
+    });
+}
+
+#[derive(Actor)]
+struct Renamed;
+
+#[derive(Message, Deserialize)]
+#[rtype(result = "()")]
+struct Reset;
+
+#[actix_handler(json_rpc)]
+impl Renamed {
+    #[handler(addr_name = "count")]
+    fn count_letters(&self, message: CountLetters, _ctx: &actix::Context<Self>) -> usize {
+        message.0.len()
+    }
+
+    #[handler(no_addr)]
+    fn reset(&self, _message: Reset, _ctx: &actix::Context<Self>) {}
+}
+
+#[test]
+fn test_dispatch_json_follows_addr_names() {
+    common::block_on(async move {
+        let addr = Renamed {}.start();
+        let result = addr.dispatch_json("count", json!("four")).await;
+        assert_eq!(result.unwrap(), json!(4));
+
+        // Renamed methods answer to their new name only, and internal messages to none
+        for method in ["count_letters", "reset"] {
+            let err = addr.dispatch_json(method, json!(null)).await.unwrap_err();
+            assert!(matches!(err, RenamedJsonRpcError::MethodNotFound(_)));
+        }
+    });
+}
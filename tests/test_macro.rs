@@ -1,4 +1,9 @@
-use actix::{Actor, Message, System};
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Message};
 use actix_handler_macro::{actix_handler, Actor};
 
 #[derive(Actor)]
@@ -35,22 +40,22 @@ impl GreeterImpl {
     }
 }
 
-#[test]
-fn test_message() {
-    let mut sys = System::new("actix-test-runtime");
+#[actix_handler_macro::test]
+async fn test_message() {
     let addr = Example {}.start();
-    sys.block_on(async move {
-        let _result = addr.send(Greeting {}).await.ok().unwrap();
-        let _result = addr.send(Hello {}).await.ok().unwrap();
-    });
+    addr.send(Greeting {}).await.ok().unwrap();
+    addr.send(Hello {}).await.ok().unwrap();
 }
 
-#[test]
-fn test_addr_trait() {
-    let mut sys = System::new("actix-test-runtime");
+#[actix_handler_macro::test]
+async fn test_addr_trait() {
     let addr = Example {}.start();
-    sys.block_on(async move {
-        let _result = addr.greet(Greeting {}).await.ok().unwrap();
-        let _result = addr.say_hello(Hello {}).await.ok().unwrap();
-    });
+    addr.greet(Greeting {}).await.ok().unwrap();
+    addr.say_hello(Hello {}).await.ok().unwrap();
+}
+
+#[actix_handler_macro::test]
+async fn test_recipient_addr_trait() {
+    let addr = GreeterImpl {}.start();
+    Greeter::greet(&addr, Greeting {}).await.ok().unwrap();
 }
@@ -0,0 +1,61 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Greeting {
+    name: String,
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct CountLetters(String);
+
+#[actix_handler(message_enum = "ExampleMsg")]
+impl Example {
+    fn greet(&self, message: Greeting, _ctx: &actix::Context<Self>) -> String {
+        format!("Hello {}", message.name)
+    }
+
+    fn count_letters(&self, message: CountLetters, _ctx: &actix::Context<Self>) -> usize {
+        message.0.len()
+    }
+}
+
+#[test]
+fn test_message_enum_dispatch() {
+    common::block_on(async move {
+        let addr = Example {}.start();
+        let messages: Vec<ExampleMsg> = vec![
+            Greeting {
+                name: "you".to_string(),
+            }
+            .into(),
+            CountLetters("four".to_string()).into(),
+        ];
+
+        let mut results = Vec::new();
+        for message in messages {
+            results.push(addr.send(message).await.unwrap());
+        }
+
+        match &results[0] {
+            ExampleMsgResult::Greet(greeting) => assert_eq!(greeting, "Hello you"),
+            _ => panic!("Expected a greeting"),
+        }
+        match &results[1] {
+            ExampleMsgResult::CountLetters(count) => assert_eq!(*count, 4),
+            _ => panic!("Expected a count"),
+        }
+    });
+}
@@ -0,0 +1,71 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Actor, Context};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+mod protocol {
+    use actix_handler_macro::actix_protocol;
+
+    // Generated code must not pick up aliases from the surrounding module
+    #[allow(dead_code)]
+    type Result<T> = std::result::Result<T, String>;
+    #[allow(dead_code)]
+    type Box = ();
+
+    #[actix_protocol]
+    pub trait Greeter {
+        fn greet(&self, name: String) -> String;
+        fn set_greeting(&self, greeting: String);
+    }
+}
+
+use protocol::{Greet, Greeter, SetGreeting};
+
+#[derive(Actor)]
+struct English {
+    greeting: String,
+}
+
+#[actix_handler(implements = "protocol::Greeter")]
+impl English {
+    fn greet(&self, msg: Greet, _ctx: &Context<Self>) -> String {
+        format!("{} {}", self.greeting, msg.name)
+    }
+
+    fn set_greeting(&mut self, msg: SetGreeting, _ctx: &mut Context<Self>) {
+        self.greeting = msg.greeting;
+    }
+}
+
+async fn greet_twice<G: Greeter>(greeter: &G) -> (String, String) {
+    let first = greeter.greet("Alice".to_string()).await.unwrap();
+    greeter.set_greeting("Hi".to_string()).await.unwrap();
+    let second = greeter.greet("Bob".to_string()).await.unwrap();
+    (first, second)
+}
+
+#[test]
+fn test_protocol_client_trait() {
+    let (first, second) = common::block_on(async move {
+        let addr = English {
+            greeting: "Hello".to_string(),
+        }
+        .start();
+        greet_twice(&addr).await
+    });
+    assert_eq!(first, "Hello Alice");
+    assert_eq!(second, "Hi Bob");
+}
+
+#[test]
+fn test_protocol_handler_trait() {
+    use protocol::GreeterHandler;
+
+    fn assert_handler<H: GreeterHandler>() {}
+    assert_handler::<English>();
+}
@@ -0,0 +1,119 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::time::Duration;
+
+use actix::{Actor, ActorContext, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Message, Clone)]
+#[rtype(result = "()")]
+struct PriceChanged(usize);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct SetPrice(usize);
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct CountSubscribers;
+
+#[derive(Actor, Default)]
+#[publishes(PriceChanged)]
+struct Ticker {
+    #[publishes(subscribers)]
+    subscribers: TickerSubscribers,
+}
+
+#[actix_handler]
+impl Ticker {
+    fn set_price(&mut self, msg: SetPrice, ctx: &mut Context<Self>) {
+        self.publish_price_changed(ctx, PriceChanged(msg.0));
+    }
+
+    fn count_subscribers(&self, _msg: CountSubscribers, _ctx: &Context<Self>) -> usize {
+        self.subscribers.price_changed.len()
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct GetTotal;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Stop;
+
+#[derive(Actor, Default)]
+struct Watcher {
+    prices: Vec<usize>,
+}
+
+#[actix_handler]
+impl Watcher {
+    fn price_changed(&mut self, msg: PriceChanged, _ctx: &mut Context<Self>) {
+        self.prices.push(msg.0);
+    }
+
+    fn get_total(&self, _msg: GetTotal, _ctx: &Context<Self>) -> usize {
+        self.prices.iter().sum()
+    }
+
+    fn stop(&mut self, _msg: Stop, ctx: &mut Context<Self>) {
+        ctx.stop();
+    }
+}
+
+#[test]
+fn test_subscribers_receive_published_events() {
+    let (first, second) = common::block_on(async move {
+        let ticker = Ticker::default().start();
+        let first = Watcher::default().start();
+        let second = Watcher::default().start();
+        ticker
+            .send(TickerSubscribe(first.clone().recipient()))
+            .await
+            .unwrap();
+        ticker
+            .send(TickerSubscribe(second.clone().recipient()))
+            .await
+            .unwrap();
+
+        ticker.set_price(SetPrice(1)).await.unwrap();
+        ticker
+            .send(TickerUnsubscribe(second.clone().recipient()))
+            .await
+            .unwrap();
+        ticker.set_price(SetPrice(2)).await.unwrap();
+        common::sleep(Duration::from_millis(10)).await;
+
+        (
+            first.get_total(GetTotal).await.unwrap(),
+            second.get_total(GetTotal).await.unwrap(),
+        )
+    });
+    assert_eq!(first, 3);
+    assert_eq!(second, 1);
+}
+
+#[test]
+fn test_stopped_subscribers_are_dropped() {
+    let subscribers = common::block_on(async move {
+        let ticker = Ticker::default().start();
+        let watcher = Watcher::default().start();
+        ticker
+            .send(TickerSubscribe(watcher.clone().recipient()))
+            .await
+            .unwrap();
+        watcher.stop(Stop).await.unwrap();
+        common::sleep(Duration::from_millis(10)).await;
+
+        ticker.set_price(SetPrice(1)).await.unwrap();
+        ticker.count_subscribers(CountSubscribers).await.unwrap()
+    });
+    assert_eq!(subscribers, 0);
+}
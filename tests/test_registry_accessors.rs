@@ -0,0 +1,57 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Context, Message};
+use actix_handler_macro::{actix_handler, Actor, ArbiterService, Supervised, SystemService};
+
+mod common;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Add(usize);
+
+#[derive(Actor, Supervised, ArbiterService, Default)]
+struct Adder {
+    total: usize,
+}
+
+#[actix_handler]
+impl Adder {
+    fn add(&mut self, msg: Add, _ctx: &mut Context<Self>) -> usize {
+        self.total += msg.0;
+        self.total
+    }
+}
+
+#[derive(Actor, Supervised, SystemService, Default)]
+struct RequestCounter {
+    total: usize,
+}
+
+#[actix_handler]
+impl RequestCounter {
+    fn count(&mut self, msg: Add, _ctx: &mut Context<Self>) -> usize {
+        self.total += msg.0;
+        self.total
+    }
+}
+
+#[test]
+fn test_arbiter_registry_accessor() {
+    let total = common::block_on(async move {
+        adder_addr().add(Add(3)).await.unwrap();
+        adder_addr().add(Add(5)).await.unwrap()
+    });
+    assert_eq!(total, 8);
+}
+
+#[test]
+fn test_system_registry_accessor() {
+    let total = common::block_on(async move {
+        request_counter_addr().count(Add(1)).await.unwrap();
+        request_counter_addr().count(Add(1)).await.unwrap()
+    });
+    assert_eq!(total, 2);
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "remote")]
+
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::io::{self, Cursor, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use actix::{Actor, Message};
+use actix_handler_macro::{actix_handler, Actor};
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+
+mod common;
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "String")]
+struct Greeting {
+    name: String,
+}
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "usize")]
+struct CountLetters(String);
+
+#[actix_handler(remote)]
+impl Example {
+    fn greet(&self, message: Greeting, _ctx: &actix::Context<Self>) -> String {
+        format!("Hello {}", message.name)
+    }
+
+    fn count_letters(&self, message: CountLetters, _ctx: &actix::Context<Self>) -> usize {
+        message.0.len()
+    }
+}
+
+#[test]
+fn test_remote_over_tcp() {
+    let (greeting, count) = common::block_on(async move {
+        let server = ExampleRemoteServer::new(Example {}.start());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            server.serve(stream).unwrap();
+        });
+
+        let (tx, rx) = oneshot::channel();
+        thread::spawn(move || {
+            let client = ExampleRemoteClient::new(TcpStream::connect(local_addr).unwrap());
+            let greeting = client.greet(Greeting {
+                name: "you".to_string(),
+            });
+            let count = client.count_letters(CountLetters("four".to_string()));
+            let _ = tx.send((greeting.unwrap(), count.unwrap()));
+        });
+
+        rx.await.unwrap()
+    });
+
+    assert_eq!(greeting, "Hello you");
+    assert_eq!(count, 4);
+}
+
+#[derive(Actor)]
+struct Limited;
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "()")]
+struct Reset;
+
+#[actix_handler(remote, max_frame_bytes = 64)]
+impl Limited {
+    #[handler(addr_name = "say_hello")]
+    fn greet(&self, message: Greeting, _ctx: &actix::Context<Self>) -> String {
+        format!("Hello {}", message.name)
+    }
+
+    #[handler(no_addr)]
+    fn reset(&self, _message: Reset, _ctx: &actix::Context<Self>) {}
+}
+
+/// Reads from a fixed input and collects everything written
+struct Duplex {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Duplex {
+    fn new(input: Vec<u8>) -> Self {
+        Duplex {
+            input: Cursor::new(input),
+            output: Vec::new(),
+        }
+    }
+}
+
+impl Read for Duplex {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for Duplex {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_server_rejects_oversized_frames() {
+    let server = common::block_on(async move { LimitedRemoteServer::new(Limited {}.start()) });
+
+    // A 4 GiB frame is refused before anything is allocated for it
+    let mut input = u32::MAX.to_be_bytes().to_vec();
+    input.extend_from_slice(b"{}");
+    match server.serve(Duplex::new(input)) {
+        Err(LimitedRemoteError::FrameTooLarge(length)) => assert_eq!(length, u32::MAX as usize),
+        other => panic!("expected FrameTooLarge, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_client_rejects_oversized_frames() {
+    let client = LimitedRemoteClient::new(Duplex::new(Vec::new()));
+    let result = client.say_hello(Greeting {
+        name: "x".repeat(100),
+    });
+
+    assert!(matches!(result, Err(LimitedRemoteError::FrameTooLarge(_))));
+    assert!(client.stream.lock().unwrap().output.is_empty());
+}
+
+#[test]
+fn test_server_ignores_internal_messages() {
+    let server = common::block_on(async move { LimitedRemoteServer::new(Limited {}.start()) });
+
+    let body = br#"{"Reset":null}"#;
+    let mut input = (body.len() as u32).to_be_bytes().to_vec();
+    input.extend_from_slice(body);
+    assert!(matches!(
+        server.serve(Duplex::new(input)),
+        Err(LimitedRemoteError::Serde(_))
+    ));
+}
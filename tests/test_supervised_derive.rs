@@ -1,25 +1,30 @@
-use actix::{Handler, Supervisor, System};
-use actix_derive::Message;
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Handler, Message, Supervisor};
 use actix_handler_macro::{Actor, Supervised};
 
+mod common;
+
 #[derive(Message)]
 #[rtype(result = "()")]
 struct Sum;
 
 #[derive(Actor, Supervised, Default)]
-#[actor(context = "::actix::SyncContext")]
 struct Adder;
 
 impl Handler<Sum> for Adder {
     type Result = <Sum as actix::Message>::Result;
-    fn handle(&mut self, _: Sum, _: &mut Self::Context) -> () {
-        ()
-    }
+    fn handle(&mut self, _: Sum, _: &mut Self::Context) {}
 }
 
 #[test]
 fn test_message() {
-    let mut sys = System::new("actix-test-runtime");
-    let addr = Supervisor::start(|_| Adder);
-    let _ = sys.block_on(addr.send(Sum)).unwrap();
+    common::block_on(async move {
+        let addr = Supervisor::start(|_| Adder);
+        addr.send(Sum).await
+    })
+    .unwrap();
 }
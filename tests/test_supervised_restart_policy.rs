@@ -0,0 +1,108 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+use actix::{Actor, ActorContext, Context, MailboxError, Message, Supervisor};
+use actix_handler_macro::{actix_handler, Actor, Supervised};
+
+mod common;
+
+thread_local! {
+    static EXHAUSTED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+fn on_exhausted(worker: &mut Worker, _ctx: &mut Context<Worker>) {
+    EXHAUSTED.with(|exhausted| exhausted.borrow_mut().push(worker.restarts.restarts()));
+}
+
+#[derive(Actor, Supervised, Default)]
+#[supervised(max_restarts = 2, within_secs = 60, on_exhausted = "on_exhausted")]
+struct Worker {
+    #[supervised(state)]
+    restarts: WorkerRestartState,
+}
+
+#[derive(Actor, Supervised, Default)]
+#[supervised(backoff = "exponential", backoff_millis = 20)]
+struct SlowWorker {
+    #[supervised(state)]
+    restarts: SlowWorkerRestartState,
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Crash;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct GetRestarts;
+
+#[actix_handler]
+impl Worker {
+    fn crash(&mut self, _message: Crash, ctx: &mut Context<Self>) {
+        ctx.stop();
+    }
+
+    fn get_restarts(&self, _message: GetRestarts, _ctx: &Context<Self>) -> usize {
+        self.restarts.restarts()
+    }
+}
+
+#[actix_handler]
+impl SlowWorker {
+    fn crash(&mut self, _message: Crash, ctx: &mut Context<Self>) {
+        ctx.stop();
+    }
+
+    fn get_restarts(&self, _message: GetRestarts, _ctx: &Context<Self>) -> usize {
+        self.restarts.restarts()
+    }
+}
+
+#[test]
+fn test_restarts_are_counted() {
+    let restarts = common::block_on(async move {
+        let addr = Supervisor::start(|_| Worker::default());
+        addr.crash(Crash).await.unwrap();
+        addr.crash(Crash).await.unwrap();
+        addr.get_restarts(GetRestarts).await.unwrap()
+    });
+    assert_eq!(restarts, 2);
+    assert!(EXHAUSTED.with(|exhausted| exhausted.borrow().is_empty()));
+}
+
+#[test]
+fn test_exhausted_budget_stops_the_actor() {
+    let (reply, connected) = common::block_on(async move {
+        let addr = Worker::default().start();
+        for _ in 0..3 {
+            addr.crash(Crash).await.unwrap();
+        }
+        let reply = addr.get_restarts(GetRestarts).await;
+        (reply, addr.connected())
+    });
+    assert!(matches!(reply, Err(MailboxError::Closed)));
+    assert!(!connected);
+    assert_eq!(
+        EXHAUSTED.with(|exhausted| exhausted.borrow().clone()),
+        vec![3]
+    );
+}
+
+#[test]
+fn test_exponential_backoff_delays_restarts() {
+    let elapsed = common::block_on(async move {
+        let addr = SlowWorker::default().start();
+        let start = Instant::now();
+        addr.crash(Crash).await.unwrap();
+        addr.crash(Crash).await.unwrap();
+        addr.get_restarts(GetRestarts).await.unwrap();
+        start.elapsed()
+    });
+    // 20ms after the first restart, 40ms after the second
+    assert!(elapsed >= Duration::from_millis(60), "{:?}", elapsed);
+}
@@ -0,0 +1,55 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::time::Duration;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Actor, Default)]
+struct Counter {
+    count: usize,
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Increment;
+
+#[actix_handler]
+impl Counter {
+    fn increment(&mut self, _msg: Increment, _ctx: &mut Context<Self>) -> usize {
+        self.count += 1;
+        self.count
+    }
+}
+
+#[actix_handler_macro::test]
+async fn test_runs_on_a_system() {
+    let addr = Counter::default().start();
+    assert_eq!(addr.increment(Increment).await.unwrap(), 1);
+    assert_eq!(addr.increment(Increment).await.unwrap(), 2);
+}
+
+#[actix_handler_macro::test(timeout_secs = 5)]
+async fn test_finishes_within_timeout() {
+    common::sleep(Duration::from_millis(10)).await;
+    let addr = Counter::default().start();
+    assert_eq!(addr.increment(Increment).await.unwrap(), 1);
+}
+
+#[actix_handler_macro::test(timeout_millis = 50)]
+#[should_panic(expected = "test `test_fails_on_timeout` timed out after 50ms")]
+async fn test_fails_on_timeout() {
+    common::sleep(Duration::from_secs(60)).await;
+}
+
+#[actix_handler_macro::test]
+async fn test_returns_result() -> Result<(), actix::MailboxError> {
+    let addr = Counter::default().start();
+    assert_eq!(addr.increment(Increment).await?, 1);
+    Ok(())
+}
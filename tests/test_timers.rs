@@ -0,0 +1,60 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::time::Duration;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+#[derive(Actor, Default)]
+#[actor(timers)]
+struct Heartbeat {
+    beats: usize,
+    warmed_up: bool,
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct GetBeats;
+
+#[derive(Message)]
+#[rtype(result = "bool")]
+struct IsWarmedUp;
+
+#[actix_handler]
+impl Heartbeat {
+    #[interval(millis = 5)]
+    fn beat(&mut self, _ctx: &mut Context<Self>) {
+        self.beats += 1;
+    }
+
+    #[delayed(millis = 10)]
+    fn warm_up(&mut self, _ctx: &mut Context<Self>) {
+        self.warmed_up = true;
+    }
+
+    fn get_beats(&self, _message: GetBeats, _ctx: &Context<Self>) -> usize {
+        self.beats
+    }
+
+    fn is_warmed_up(&self, _message: IsWarmedUp, _ctx: &Context<Self>) -> bool {
+        self.warmed_up
+    }
+}
+
+#[test]
+fn test_timers_start_with_the_actor() {
+    let (beats, warmed_up) = common::block_on(async move {
+        let addr = Heartbeat::default().start();
+        common::sleep(Duration::from_millis(100)).await;
+        let beats = addr.get_beats(GetBeats).await.unwrap();
+        let warmed_up = addr.is_warmed_up(IsWarmedUp).await.unwrap();
+        (beats, warmed_up)
+    });
+    assert!(beats >= 3, "expected at least 3 beats, got {}", beats);
+    assert!(warmed_up);
+}
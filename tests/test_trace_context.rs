@@ -0,0 +1,74 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::cell::Cell;
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+thread_local! {
+    static CORRELATION_ID: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+struct CorrelationId(Option<u64>);
+
+/// Puts back the previous correlation id when dropped
+struct CorrelationGuard(Option<u64>);
+
+impl Drop for CorrelationGuard {
+    fn drop(&mut self) {
+        CORRELATION_ID.with(|id| id.set(self.0));
+    }
+}
+
+impl CorrelationId {
+    fn capture() -> Self {
+        CorrelationId(CORRELATION_ID.with(|id| id.get()))
+    }
+
+    fn enter(self) -> CorrelationGuard {
+        CorrelationGuard(CORRELATION_ID.with(|id| id.replace(self.0)))
+    }
+}
+
+#[derive(Actor)]
+struct Worker;
+
+#[derive(Message)]
+#[rtype(result = "Option<u64>")]
+struct WhoAsked;
+
+#[actix_handler(trace_context = "CorrelationId")]
+impl Worker {
+    fn who_asked(&self, _msg: WhoAsked, _ctx: &Context<Self>) -> Option<u64> {
+        CORRELATION_ID.with(|id| id.get())
+    }
+}
+
+#[test]
+fn test_trace_context_is_restored_in_handler() {
+    let (inside, after) = common::block_on(async move {
+        let addr = Worker.start();
+        CORRELATION_ID.with(|id| id.set(Some(42)));
+        let request = addr.who_asked(WhoAsked);
+        // The id has to travel with the message, not through the thread local
+        CORRELATION_ID.with(|id| id.set(None));
+        let inside = request.await.unwrap();
+        (inside, CORRELATION_ID.with(|id| id.get()))
+    });
+    assert_eq!(inside, Some(42));
+    assert_eq!(after, None);
+}
+
+#[test]
+fn test_plain_messages_are_still_handled() {
+    let inside = common::block_on(async move {
+        let addr = Worker.start();
+        addr.send(WhoAsked).await.unwrap()
+    });
+    assert_eq!(inside, None);
+}
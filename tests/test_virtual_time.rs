@@ -0,0 +1,73 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use std::time::{Duration, Instant};
+
+use actix::{Actor, Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+mod common;
+
+use actix_handler_sim as sim;
+
+#[derive(Actor, Default)]
+#[actor(timers)]
+struct Janitor {
+    sweeps: usize,
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Sweep;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct CountSweeps;
+
+#[actix_handler(ctx_ext, observer = "actix_handler_sim::Trace")]
+impl Janitor {
+    #[interval(secs = 60)]
+    fn tick(&mut self, ctx: &mut Context<Self>) {
+        ctx.notify_sweep(Sweep);
+    }
+
+    fn sweep(&mut self, _msg: Sweep, _ctx: &mut Context<Self>) {
+        self.sweeps += 1;
+    }
+
+    fn count_sweeps(&self, _msg: CountSweeps, _ctx: &Context<Self>) -> usize {
+        self.sweeps
+    }
+}
+
+#[test]
+fn test_intervals_follow_virtual_time() {
+    let started = Instant::now();
+    let (before, after) = common::block_on(async move {
+        sim::pause();
+        let addr = Janitor::default().start();
+
+        sim::advance(Duration::from_secs(59)).await;
+        let before = addr.count_sweeps(CountSweeps).await.unwrap();
+        sim::advance(Duration::from_secs(130)).await;
+        let after = addr.count_sweeps(CountSweeps).await.unwrap();
+        (before, after)
+    });
+
+    assert_eq!(before, 0);
+    assert_eq!(after, 3);
+    // Three minutes of virtual time
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        sim::Trace::take(),
+        vec![
+            "Janitor::count_sweeps",
+            "Janitor::sweep",
+            "Janitor::sweep",
+            "Janitor::sweep",
+            "Janitor::count_sweeps",
+        ]
+    );
+}
@@ -0,0 +1,7 @@
+use actix_handler_macro::{Actor, Supervised};
+
+#[derive(Actor, Supervised)]
+#[actor(crate = "not a path")]
+struct InvalidPath;
+
+fn main() {}
@@ -0,0 +1,38 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Context, Message};
+use actix_handler_macro::{actix_handler, Actor};
+
+#[derive(Actor)]
+struct Example;
+
+#[derive(Message)]
+#[rtype(result = "Result<u32, String>")]
+struct Divide(u32, u32);
+
+#[actix_handler(catch_unwind)]
+impl Example {
+    fn divide(&self, msg: Divide, _ctx: &Context<Self>) -> Result<u32, String> {
+        Ok(msg.0 / msg.1)
+    }
+}
+
+#[derive(Actor)]
+struct Counter;
+
+#[derive(Message)]
+#[rtype(result = "u32")]
+struct Count;
+
+#[actix_handler]
+impl Counter {
+    #[handler(catch_unwind)]
+    fn count(&self, _msg: Count, _ctx: &Context<Self>) -> u32 {
+        1
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use actix_handler_macro::actix_handler;
+
+struct Example;
+struct Ping;
+
+#[actix_handler]
+impl Example {
+    #[handler(gaurd = "allow_ping")]
+    fn misspelled(&mut self, _message: Ping) {}
+
+    #[handler(no_adr)]
+    fn unknown_flag(&mut self, _message: Ping) {}
+
+    #[handler(guard = "not a path")]
+    fn malformed_path(&mut self, _message: Ping) {}
+
+    #[handler(addr_name = 5)]
+    fn not_a_string(&mut self, _message: Ping) {}
+
+    #[handler(cache(ttl = 5))]
+    fn unknown_cache_option(&mut self, _message: Ping) {}
+}
+
+#[actix_handler(guard = "not a path")]
+impl Example {}
+
+#[actix_handler(gaurd = "deny")]
+impl Example {}
+
+#[actix_handler(max_in_flight = "2")]
+impl Example {}
+
+fn main() {}
@@ -0,0 +1,93 @@
+#[cfg(all(feature = "actix-0-12", not(feature = "actix-0-13")))]
+extern crate actix_0_12 as actix;
+#[cfg(feature = "actix-0-13")]
+extern crate actix_0_13 as actix;
+
+use actix::{Context, Message};
+use actix_handler_macro::{actix_handler, actix_protocol, Actor};
+
+#[actix_protocol]
+trait Greeter {
+    fn greet(&self, name: String) -> String;
+    fn wave(&self) -> usize;
+}
+
+#[derive(Actor)]
+struct MissingProtocolMethod;
+
+#[actix_handler(implements = "Greeter")]
+impl MissingProtocolMethod {
+    fn greet(&self, msg: Greet, _ctx: &Context<Self>) -> String {
+        msg.name
+    }
+}
+
+#[derive(Actor)]
+struct ExtraProtocolHandler;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Shout;
+
+#[actix_handler(implements = "Greeter")]
+impl ExtraProtocolHandler {
+    fn greet(&self, msg: Greet, _ctx: &Context<Self>) -> String {
+        msg.name
+    }
+
+    fn wave(&self, _msg: Wave, _ctx: &Context<Self>) -> usize {
+        1
+    }
+
+    fn shout(&self, _msg: Shout, _ctx: &Context<Self>) {}
+}
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct Ping;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Load;
+
+#[derive(Actor)]
+struct Primary;
+
+#[actix_handler(use_recipient, trait_name = "PrimaryAddr")]
+impl Primary {
+    fn ping(&self, _msg: Ping, _ctx: &Context<Self>) -> String {
+        "primary".to_string()
+    }
+
+    fn load(&self, _msg: Load, _ctx: &Context<Self>) -> usize {
+        0
+    }
+}
+
+#[derive(Actor)]
+struct MissingAddrMethod;
+
+#[actix_handler(use_recipient, no_trait_decl, implements = "PrimaryAddr")]
+impl MissingAddrMethod {
+    fn ping(&self, _msg: Ping, _ctx: &Context<Self>) -> String {
+        "replica".to_string()
+    }
+}
+
+#[derive(Actor)]
+struct ExtraAddrHandler;
+
+#[actix_handler(use_recipient, no_trait_decl, implements = "PrimaryAddr")]
+impl ExtraAddrHandler {
+    fn ping(&self, _msg: Ping, _ctx: &Context<Self>) -> String {
+        "replica".to_string()
+    }
+
+    fn load(&self, _msg: Load, _ctx: &Context<Self>) -> usize {
+        7
+    }
+
+    fn shout(&self, _msg: Shout, _ctx: &Context<Self>) {}
+}
+
+fn main() {}
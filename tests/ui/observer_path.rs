@@ -0,0 +1,8 @@
+use actix_handler_macro::actix_handler;
+
+struct Example;
+
+#[actix_handler(observer = "not a path")]
+impl Example {}
+
+fn main() {}
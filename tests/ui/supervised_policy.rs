@@ -0,0 +1,31 @@
+use actix_handler_macro::{Actor, Supervised};
+
+#[derive(Actor, Supervised, Default)]
+#[supervised(backoff = "exponental")]
+struct MisspelledBackoff {
+    #[supervised(state)]
+    restarts: MisspelledBackoffRestartState,
+}
+
+#[derive(Actor, Supervised, Default)]
+#[supervised(on_exhausted = "not a path")]
+struct InvalidHook {
+    #[supervised(state)]
+    restarts: InvalidHookRestartState,
+}
+
+#[derive(Actor, Supervised, Default)]
+#[supervised(max_restart = 2)]
+struct UnknownKey {
+    #[supervised(state)]
+    restarts: UnknownKeyRestartState,
+}
+
+#[derive(Actor, Supervised, Default)]
+#[supervised(max_restarts = "2", within_secs = 60)]
+struct NotAnInteger {
+    #[supervised(state)]
+    restarts: NotAnIntegerRestartState,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use actix_handler_macro::Actor;
+
+#[derive(Actor, Default)]
+#[actor(threads = 2)]
+struct Adder;
+
+fn main() {}
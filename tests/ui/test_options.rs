@@ -0,0 +1,13 @@
+#[actix_handler_macro::test(timeout = 1)]
+async fn unknown_key() {}
+
+#[actix_handler_macro::test(timeout_secs = "1")]
+async fn not_an_integer() {}
+
+#[actix_handler_macro::test(timeout_secs = 18446744073709551615)]
+async fn too_long() {}
+
+#[actix_handler_macro::test(crate = "not a path")]
+async fn invalid_crate() {}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use actix_handler_macro::actix_handler;
+
+struct Example;
+
+#[actix_handler]
+impl Example {
+    #[interval]
+    fn missing(&mut self) {}
+
+    #[interval(secs = 0)]
+    fn zero(&mut self) {}
+
+    #[interval(secs = "5")]
+    fn not_an_integer(&mut self) {}
+
+    #[interval(minutes = 5)]
+    fn unknown_key(&mut self) {}
+
+    #[delayed(sec = 1)]
+    fn misspelled(&mut self) {}
+}
+
+fn main() {}